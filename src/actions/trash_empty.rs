@@ -5,24 +5,29 @@ use std::{
         remove_dir_all, 
         remove_file
     }, 
-    io::Result,
+    io::{ErrorKind, Result},
     path::Path
 };
 
-use crate::common::{
-    freedesktop_home_trash_files_dir, 
-    freedesktop_home_trash_info_dir
+use crate::{
+    actions::trash_remove::trash_remove,
+    common::{
+        all_trash_dirs,
+        get_all_trash_contents,
+        trash_files_dir, 
+        trash_info_dir
+    },
+    directory_sizes::directory_sizes_path,
+    trash_filter::TrashFilter
 };
 
 fn rm_dir_contents(path: &Path) -> Result<()> {
     if exists(path)? {
-        for dir_entry in read_dir(path)? {
-            if let Ok(dir_entry) = dir_entry {
-                if dir_entry.path().is_dir() {
-                    remove_dir_all(dir_entry.path())?;
-                } else {
-                    remove_file(dir_entry.path())?;
-                }
+        for dir_entry in read_dir(path)?.flatten() {
+            if dir_entry.file_type()?.is_dir() {
+                remove_dir_all(dir_entry.path())?;
+            } else {
+                remove_file(dir_entry.path())?;
             }
         }
     }
@@ -30,25 +35,32 @@ fn rm_dir_contents(path: &Path) -> Result<()> {
     Ok(())
 }
 
-fn remove_all_trashinfo_files() -> Result<()> {
-    if let Some(home_trash_info_dir) = freedesktop_home_trash_info_dir() {
-        rm_dir_contents(&home_trash_info_dir)
-    } else {
-        Ok(())
+fn empty_trash_dir(trash_dir: &Path) -> Result<()> {
+    rm_dir_contents(&trash_files_dir(trash_dir))?;
+    rm_dir_contents(&trash_info_dir(trash_dir))?;
+
+    // every line of the directorysizes cache is stale once the trash is empty
+    match remove_file(directory_sizes_path(trash_dir)) {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error),
+        _ => Ok(())
     }
 }
 
-fn remove_all_trash_files() -> Result<()> {
-    if let Some(home_trash_files_dir) = freedesktop_home_trash_files_dir() {
-        rm_dir_contents(&home_trash_files_dir)
-    } else {
-        Ok(())
+/// Empties the home trash and every top directory trash
+pub fn trash_empty() -> Result<()> {
+    for trash_dir in all_trash_dirs() {
+        empty_trash_dir(&trash_dir)?;
     }
+
+    Ok(())
 }
 
-pub fn trash_empty() -> Result<()> {
-    match remove_all_trash_files() {
-        Ok(_) => remove_all_trashinfo_files(),
-        Err(error) => Err(error)
+/// Permanently deletes the entries of every trash matching `filter`, returns how many were deleted
+pub fn trash_empty_matching(filter: &TrashFilter) -> Result<usize> {
+    let trash_contents = filter.apply(get_all_trash_contents()?);
+    for trash_entry in &trash_contents {
+        trash_remove(trash_entry)?;
     }
+
+    Ok(trash_contents.len())
 }
\ No newline at end of file
@@ -1,59 +1,475 @@
 use std::{
     fs::read_dir, 
-    io::Result, 
-    path::Path
+    io::{Error, ErrorKind, Result}, 
+    path::Path,
+    str::FromStr
 };
 
-use tabled::{settings::Style, Table};
-use crate::{common::*, tree::Tree};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tabled::{builder::Builder, settings::Style};
+use crate::{
+    common::*,
+    directory_sizes::{count_items, trash_entry_size},
+    trash_filter::TrashFilter,
+    trash_info::{TrashEntryType, TrashInfo},
+    tree::Tree
+};
 
 fn files_tree_label<P: AsRef<Path>>(p: P) -> String {
-    let name = p.as_ref()
-        .file_name()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .to_owned();
-
-    if name.eq(&String::from("files")) {
+    match p.as_ref().file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => p.as_ref().to_string_lossy().into_owned()
+    }
+}
+
+/// The label of the root of a trash directory's tree: the home trash is the "System Trash",
+/// top directory trashes are labelled with their location
+fn trash_dir_tree_label(trash_dir: &Path) -> String {
+    if freedesktop_home_trash_dir().is_some_and(|home_trash_dir| home_trash_dir == trash_dir) {
         String::from("System Trash")
     } else {
-        name
+        trash_dir.display().to_string()
+    }
+}
+
+fn files_tree<P: AsRef<Path>>(p: P, label: String) -> Result<Tree<String>> {
+    let mut root = Tree::new(label);
+
+    for entry in read_dir(&p)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            root.push(files_tree(entry.path(), files_tree_label(entry.path()))?);
+        } else {
+            root.push(Tree::new(files_tree_label(entry.path())));
+        }
+    }
+
+    Ok(root)
+}
+
+/// The JSON form of the tree of a trash directory's `files`: an array of `{"name", "type"}` objects,
+/// directories also have a `children` array
+fn files_tree_json(p: &Path) -> Result<Value> {
+    let mut children = vec![];
+
+    for entry in read_dir(p)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        children.push(if file_type.is_dir() {
+            json!({ "name": name, "type": TrashEntryType::Directory.to_string(), "children": files_tree_json(&entry.path())? })
+        } else if file_type.is_symlink() {
+            json!({ "name": name, "type": TrashEntryType::Symlink.to_string() })
+        } else {
+            json!({ "name": name, "type": TrashEntryType::File.to_string() })
+        });
+    }
+
+    Ok(Value::Array(children))
+}
+
+/// How `oscar list` prints the trash contents
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ListFormat {
+    #[default]
+    Table,
+    /// A JSON array of `TrashListRecord`s
+    Json,
+    /// One `TrashListRecord` JSON object per line
+    Ndjson,
+    /// `TrashListRecord`s with a header line
+    Csv,
+    /// Like CSV, separated with tabs
+    Tsv
+}
+
+impl FromStr for ListFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(ListFormat::Table),
+            "json" => Ok(ListFormat::Json),
+            "ndjson" => Ok(ListFormat::Ndjson),
+            "csv" => Ok(ListFormat::Csv),
+            "tsv" => Ok(ListFormat::Tsv),
+            _ => Err(format!("unknown format `{s}`, expected one of table, json, ndjson, csv, tsv"))
+        }
+    }
+}
+
+/// A trash entry in the machine readable formats. The field names and their meaning are stable,
+/// paths that are not valid UTF-8 have their invalid bytes replaced with U+FFFD.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct TrashListRecord {
+    /// The name of the entry in the trash, i.e. its trashinfo file name without the `.trashinfo` extension
+    pub name: String,
+
+    /// The absolute path the entry was deleted from
+    pub original_path: String,
+
+    /// When the entry was deleted, in ISO 8601 local time (`2004-08-31T22:32:08`)
+    pub deletion_date: String,
+
+    /// The trash directory the entry lives in
+    pub trash_dir: String,
+
+    /// The disk usage of the entry in bytes, only computed when sizes are requested (null or empty otherwise)
+    pub size: Option<u64>,
+
+    /// `file`, `dir` or `symlink`
+    #[serde(rename = "type")]
+    pub entry_type: Option<String>
+}
+
+impl TrashListRecord {
+    pub fn new(trash_info: &TrashInfo, with_size: bool) -> TrashListRecord {
+        TrashListRecord {
+            name: trash_info.path.to_string_lossy().into_owned(),
+            original_path: trash_info.full_path.to_string_lossy().into_owned(),
+            deletion_date: trash_info.deletion_date.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            trash_dir: trash_info.trash_dir.to_string_lossy().into_owned(),
+            size: if with_size { trash_entry_size(trash_info).ok() } else { None },
+            entry_type: trash_info.entry_type().ok().map(|entry_type| entry_type.to_string())
+        }
+    }
+}
+
+/// The fields of `TrashListRecord`, in order
+const TRASH_LIST_RECORD_FIELDS: [&str; 6] = ["name", "original_path", "deletion_date", "trash_dir", "size", "type"];
+
+fn delimited_records(records: &[TrashListRecord], delimiter: u8) -> Result<String> {
+    // the header is written by hand so that it is there even when there are no records
+    let mut writer = csv::WriterBuilder::new().delimiter(delimiter).has_headers(false).from_writer(vec![]);
+    writer.write_record(TRASH_LIST_RECORD_FIELDS)?;
+    for record in records {
+        writer.serialize(record)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|error| Error::other(error.to_string()))?;
+    String::from_utf8(bytes).map_err(Error::other)
+}
+
+/// A column of the `oscar list` table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListColumn {
+    /// The name of the entry in the trash
+    Name,
+    /// The directory the entry was deleted from
+    Location,
+    /// The full path the entry was deleted from
+    OriginalPath,
+    DeletionDate,
+    Type,
+    Size,
+    /// The number of items inside directories
+    Items,
+    /// The trash directory the entry lives in
+    TrashDir
+}
+
+impl ListColumn {
+    pub fn header(&self) -> &'static str {
+        match self {
+            ListColumn::Name => "Path",
+            ListColumn::Location => "Original Location",
+            ListColumn::OriginalPath => "Original Path",
+            ListColumn::DeletionDate => "Deletion Date",
+            ListColumn::Type => "Type",
+            ListColumn::Size => "Size",
+            ListColumn::Items => "Items",
+            ListColumn::TrashDir => "Trash"
+        }
+    }
+
+    /// Whether the column requires walking trashed directories
+    fn is_expensive(&self) -> bool {
+        matches!(self, ListColumn::Size | ListColumn::Items)
+    }
+}
+
+impl FromStr for ListColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(ListColumn::Name),
+            "location" => Ok(ListColumn::Location),
+            "path" => Ok(ListColumn::OriginalPath),
+            "date" => Ok(ListColumn::DeletionDate),
+            "type" => Ok(ListColumn::Type),
+            "size" => Ok(ListColumn::Size),
+            "items" => Ok(ListColumn::Items),
+            "trash" => Ok(ListColumn::TrashDir),
+            _ => Err(format!("unknown column `{s}`, expected one of name, location, path, date, type, size, items, trash"))
+        }
+    }
+}
+
+/// The order of the `oscar list` entries
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ListSort {
+    /// By name, alphabetically
+    Name,
+    /// By deletion date, most recent first
+    #[default]
+    Date,
+    /// By size, largest first
+    Size,
+    /// By original path, alphabetically
+    Path
+}
+
+impl FromStr for ListSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(ListSort::Name),
+            "date" => Ok(ListSort::Date),
+            "size" => Ok(ListSort::Size),
+            "path" => Ok(ListSort::Path),
+            _ => Err(format!("unknown sort order `{s}`, expected one of name, date, size, path"))
+        }
+    }
+}
+
+/// Sorts trash entries in place, `reverse` flips the order
+pub fn sort_trash_contents(trash_contents: &mut [TrashInfo], sort: ListSort, reverse: bool) {
+    match sort {
+        ListSort::Name => trash_contents.sort_by(|a, b| a.path.cmp(&b.path)),
+        ListSort::Date => trash_contents.sort_by_key(|trash_info| std::cmp::Reverse(trash_info.deletion_date)),
+        ListSort::Size => trash_contents.sort_by_cached_key(|trash_info| std::cmp::Reverse(trash_entry_size(trash_info).unwrap_or_default())),
+        ListSort::Path => trash_contents.sort_by(|a, b| a.full_path.cmp(&b.full_path))
+    }
+
+    if reverse {
+        trash_contents.reverse();
+    }
+}
+
+/// Options for `trash_list_with_options`
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    /// List trash contents recursively, as a tree
+    pub recursive: bool,
+
+    /// Add size and item count columns. Off by default because directories have to be walked to compute them.
+    pub sizes: bool,
+
+    /// Show sizes as a number of bytes instead of a human readable size
+    pub bytes: bool,
+
+    /// Show the full original path of every entry instead of its name and original location
+    pub full_path: bool,
+
+    /// The columns to show, in order. Empty for the default columns.
+    pub columns: Vec<ListColumn>,
+
+    /// How to print the trash contents: a table, or one of the machine readable formats
+    pub format: ListFormat,
+
+    /// The order of the entries, most recently deleted first by default
+    pub sort: ListSort,
+
+    /// Reverse the sort order
+    pub reverse: bool,
+
+    /// Only list the entries matching this filter. The recursive listing is not filtered.
+    pub filter: TrashFilter
+}
+
+impl ListOptions {
+    /// The columns of the table: the chosen ones, or the defaults for the other options
+    pub fn columns(&self) -> Vec<ListColumn> {
+        if !self.columns.is_empty() {
+            return self.columns.clone();
+        }
+
+        let mut columns = if self.full_path {
+            vec![ListColumn::OriginalPath]
+        } else {
+            vec![ListColumn::Name, ListColumn::Location]
+        };
+        columns.extend([ListColumn::DeletionDate, ListColumn::Type]);
+
+        if self.sizes {
+            columns.extend([ListColumn::Size, ListColumn::Items]);
+        }
+
+        columns
+    }
+}
+
+/// Formats a number of bytes with binary units, e.g. `1.5 KiB`
+pub fn human_readable_size(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Describes how long ago `date` was relative to `now`, e.g. `3 hours ago`
+pub fn relative_date(date: NaiveDateTime, now: NaiveDateTime) -> String {
+    const UNITS: [(&str, i64); 6] = [
+        ("year", 365 * 24 * 60 * 60),
+        ("month", 30 * 24 * 60 * 60),
+        ("week", 7 * 24 * 60 * 60),
+        ("day", 24 * 60 * 60),
+        ("hour", 60 * 60),
+        ("minute", 60)
+    ];
+
+    let seconds = (now - date).num_seconds();
+    for (unit, unit_seconds) in UNITS {
+        let count = seconds / unit_seconds;
+        if count > 0 {
+            return format!("{count} {unit}{} ago", if count == 1 { "" } else { "s" });
+        }
+    }
+
+    String::from("just now")
+}
+
+/// `1 item`, `2 items`...
+pub fn item_count(count: usize) -> String {
+    format!("{count} {}", if count == 1 { "item" } else { "items" })
+}
+
+fn format_size(bytes: u64, options: &ListOptions) -> String {
+    if options.bytes {
+        bytes.to_string()
+    } else {
+        human_readable_size(bytes)
     }
 }
 
-fn files_tree<P: AsRef<Path>>(p: P) -> Result<Tree<String>> {
-    let result = read_dir(&p)?.filter_map(|e| e.ok()).fold(
-        Tree::new(files_tree_label(p.as_ref().canonicalize()?)),
-        |mut root, entry| {
-            let dir = entry.metadata().unwrap();
-            if dir.is_dir() {
-                root.push(files_tree(entry.path()).unwrap());
-            } else {
-                root.push(Tree::new(files_tree_label(entry.path())));
+/// The size and item count of an entry, only computed when one of them is shown
+struct EntrySizes {
+    size: Option<u64>,
+    item_count: Option<u64>
+}
+
+impl EntrySizes {
+    fn of(trash_info: &TrashInfo, entry_type: Option<TrashEntryType>) -> EntrySizes {
+        EntrySizes {
+            size: trash_entry_size(trash_info).ok(),
+            item_count: match entry_type {
+                Some(TrashEntryType::Directory) => count_items(&trash_info.trash_file_path()).ok(),
+                _ => None
             }
-            root
-        },
-    );
+        }
+    }
+}
 
-    Ok(result)
+fn table_cell(column: ListColumn, trash_info: &TrashInfo, entry_type: Option<TrashEntryType>, sizes: Option<&EntrySizes>, options: &ListOptions) -> String {
+    match column {
+        ListColumn::Name => trash_info.path.to_string_lossy().into_owned(),
+        ListColumn::Location => trash_info.full_path.parent().unwrap_or(Path::new("")).to_string_lossy().into_owned(),
+        ListColumn::OriginalPath => trash_info.full_path.to_string_lossy().into_owned(),
+        ListColumn::DeletionDate => trash_info.deletion_date.to_string(),
+        ListColumn::Type => entry_type.map(|entry_type| entry_type.to_string()).unwrap_or_default(),
+        ListColumn::Size => sizes.and_then(|sizes| sizes.size).map(|size| format_size(size, options)).unwrap_or_default(),
+        ListColumn::Items => sizes.and_then(|sizes| sizes.item_count).map(|count| count.to_string()).unwrap_or_default(),
+        ListColumn::TrashDir => trash_info.trash_dir.to_string_lossy().into_owned()
+    }
+}
+
+/// Renders the table of trash entries, with a footer row holding the totals
+pub fn trash_contents_table(trash_contents: &[TrashInfo], options: &ListOptions) -> String {
+    let columns = options.columns();
+    let compute_sizes = columns.iter().any(ListColumn::is_expensive);
+
+    let mut builder = Builder::default();
+    builder.push_record(columns.iter().map(ListColumn::header));
+
+    let mut total_size = 0;
+    for trash_info in trash_contents {
+        let entry_type = trash_info.entry_type().ok();
+        let sizes = compute_sizes.then(|| EntrySizes::of(trash_info, entry_type));
+        total_size += sizes.as_ref().and_then(|sizes| sizes.size).unwrap_or_default();
+
+        builder.push_record(columns.iter().map(|column| table_cell(*column, trash_info, entry_type, sizes.as_ref(), options)));
+    }
+
+    let footer = columns.iter().enumerate().map(|(i, column)| match column {
+        _ if i == 0 => item_count(trash_contents.len()),
+        ListColumn::Size => format_size(total_size, options),
+        _ => String::new()
+    });
+    builder.push_record(footer);
+
+    let mut table = builder.build();
+    table.with(Style::modern_rounded());
+
+    table.to_string()
+}
+
+/// Renders the trash entries in the format chosen in `options`
+pub fn format_trash_contents(trash_contents: &[TrashInfo], options: &ListOptions) -> Result<String> {
+    let records = || trash_contents.iter().map(|trash_info| TrashListRecord::new(trash_info, options.sizes)).collect::<Vec<_>>();
+
+    match options.format {
+        ListFormat::Table => Ok(trash_contents_table(trash_contents, options)),
+        ListFormat::Json => Ok(serde_json::to_string_pretty(&records())? + "\n"),
+        ListFormat::Ndjson => records()
+            .iter()
+            .map(|record| Ok(serde_json::to_string(record)? + "\n"))
+            .collect(),
+        ListFormat::Csv => delimited_records(&records(), b','),
+        ListFormat::Tsv => delimited_records(&records(), b'\t')
+    }
 }
 
 pub fn trash_list(recursive: bool) -> Result<()> {
+    trash_list_with_options(&ListOptions { recursive, ..ListOptions::default() })
+}
+
+pub fn trash_list_with_options(options: &ListOptions) -> Result<()> {
     create_home_trash_dir_if_not_exists()?;
 
-    if recursive {
-        let trash_files_dir = freedesktop_home_trash_files_dir().unwrap();
-        let tree = files_tree(trash_files_dir)?;
-        println!("{tree}");
-    } else {
-        let mut trash_contents = get_home_trash_contents()?;
-        trash_contents.sort_by(|a, b| b.deletion_date.cmp(&a.deletion_date));
+    if options.recursive && options.format == ListFormat::Json {
+        let mut trash_dirs = vec![];
+        for trash_dir in all_trash_dirs() {
+            let trash_files_dir = trash_files_dir(&trash_dir);
+            if trash_files_dir.is_dir() {
+                trash_dirs.push(json!({
+                    "name": trash_dir_tree_label(&trash_dir),
+                    "trash_dir": trash_dir.to_string_lossy(),
+                    "children": files_tree_json(&trash_files_dir)?
+                }));
+            }
+        }
 
-        let mut table = Table::new(trash_contents);
-        table.with(Style::modern_rounded());
+        println!("{}", serde_json::to_string_pretty(&trash_dirs)?);
+    } else if options.recursive && options.format != ListFormat::Table {
+        return Err(Error::new(ErrorKind::InvalidInput, "the recursive listing can only be printed as a table or as JSON"));
+    } else if options.recursive {
+        for trash_dir in all_trash_dirs() {
+            let trash_files_dir = trash_files_dir(&trash_dir);
+            if trash_files_dir.is_dir() {
+                let tree = files_tree(trash_files_dir, trash_dir_tree_label(&trash_dir))?;
+                println!("{tree}");
+            }
+        }
+    } else {
+        let mut trash_contents = options.filter.apply(get_all_trash_contents()?);
+        sort_trash_contents(&mut trash_contents, options.sort, options.reverse);
 
-        print!("{}", table.to_string());
+        print!("{}", format_trash_contents(&trash_contents, options)?);
     }
 
     Ok(())
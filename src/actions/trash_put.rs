@@ -1,79 +1,218 @@
 use std::{
+    ffi::OsString,
     fs::{
-        canonicalize,
-        exists,
-        rename,
+        read_dir,
+        remove_file,
+        symlink_metadata,
+        File,
+        OpenOptions,
     },
     io::{
         Error,
         ErrorKind,
         Result,
+        Write,
     },
-    path::PathBuf,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
 };
 
 use chrono::Local;
-use configparser::ini::Ini;
 
 use crate::{
     common::*,
     constants::*,
+    directory_sizes::add_directory_size,
+    file_move::move_path,
+    protected_paths::check_not_protected,
     string_encode::encode_filename,
 };
 
-fn create_trash_info_entry(path: &PathBuf) -> Result<()> {
-    create_home_trash_dir_if_not_exists()?;
+/// Builds the n-th candidate name for a trash entry: `notes.txt`, `notes.2.txt`, `notes.3.txt`...
+fn trash_entry_name_candidate(filename: &Path, n: usize) -> OsString {
+    if n == 1 {
+        return filename.as_os_str().to_owned();
+    }
 
-    let trash_info_section_header = String::from(TRASH_INFO_SECTION_HEADER);
-    let mut trash_info_ini = Ini::new();
+    let mut candidate = filename.file_stem().unwrap_or(filename.as_os_str()).to_owned();
+    candidate.push(format!(".{n}"));
+    if let Some(extension) = filename.extension() {
+        candidate.push(".");
+        candidate.push(extension);
+    }
 
-    let now = Local::now();
+    candidate
+}
 
-    trash_info_ini.set(
-        &trash_info_section_header, 
-        TRASH_INFO_PATH_KEY, 
-        Some(String::from(encode_filename(path.to_str().unwrap())))
+/// Reserves a unique name in the trash by atomically creating its `.trashinfo` file,
+/// as required by the Freedesktop.org spec. Returns the reserved name along with the
+/// newly created (still empty) trashinfo file.
+fn reserve_trash_entry(trash_dir: &Path, filename: &Path) -> Result<(OsString, File)> {
+    let (trash_info_dir, trash_files_dir) = (
+        trash_info_dir(trash_dir),
+        trash_files_dir(trash_dir)
     );
 
-    trash_info_ini.set(
-        &trash_info_section_header, 
-        TRASH_INFO_DELETION_DATE_KEY, 
-        Some(now.format("%Y-%m-%dT%H:%M:%S").to_string())
-    );
+    for n in 1.. {
+        let candidate = trash_entry_name_candidate(filename, n);
+
+        // a payload without a trashinfo file may still be sitting in the trash, never clobber it (even a dangling symlink)
+        if exists_without_following(&trash_files_dir.join(&candidate))? {
+            continue;
+        }
+
+        let trash_info_path = with_trashinfo_extension(&trash_info_dir.join(&candidate));
+        match OpenOptions::new().write(true).create_new(true).open(trash_info_path) {
+            Ok(file) => return Ok((candidate, file)),
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error)
+        }
+    }
 
-    if let Some(filename) = path.file_name() {
-        let trash_info_directory = freedesktop_home_trash_info_dir().unwrap();
-        let trash_info_ini_path = with_trashinfo_extension(&trash_info_directory.join(filename));
+    unreachable!()
+}
 
-        trash_info_ini.write(trash_info_ini_path)
-    } else {
-        Err(Error::new(ErrorKind::InvalidInput, format!("Cannot place {} in trash", path.display())))
+/// The path recorded in the trashinfo file: relative to the top directory for top directory trashes,
+/// absolute for the home trash
+pub fn trash_info_original_path<'p>(trash_dir: &Path, path: &'p Path) -> &'p Path {
+    match trash_dir_top_dir(trash_dir) {
+        Some(top_dir) => path.strip_prefix(top_dir).unwrap_or(path),
+        None => path
     }
 }
 
-pub fn trash_put(path: &String) -> Result<()> {
+fn create_trash_info_entry(trash_dir: &Path, path: &Path) -> Result<OsString> {
+    create_trash_dir_if_not_exists(trash_dir)?;
+
+    match path.file_name() {
+        Some(filename) => {
+            let (trash_entry_name, mut trash_info_file) = reserve_trash_entry(trash_dir, Path::new(filename))?;
+            let original_path = trash_info_original_path(trash_dir, path);
+
+            let now = Local::now();
+            let trash_info_contents = format!(
+                "[{}]\n{}={}\n{}={}\n",
+                TRASH_INFO_SECTION_HEADER,
+                TRASH_INFO_PATH_KEY,
+                encode_filename(original_path.as_os_str().as_bytes()),
+                TRASH_INFO_DELETION_DATE_KEY,
+                now.format("%Y-%m-%dT%H:%M:%S")
+            );
+
+            // the trashinfo file must be on disk before the file is moved, so that a crash never leaves
+            // a file in the trash without the information needed to restore it
+            let write_result = trash_info_file.write_all(trash_info_contents.as_bytes())
+                .and_then(|_| trash_info_file.sync_all());
+
+            match write_result {
+                Ok(_) => Ok(trash_entry_name),
+                Err(error) => {
+                    let _ = remove_file(with_trashinfo_extension(&trash_info_dir(trash_dir).join(&trash_entry_name)));
+                    Err(error)
+                }
+            }
+        },
+        None => Err(Error::new(ErrorKind::InvalidInput, format!("Cannot place {} in trash", path.display())))
+    }
+}
+
+/// Options for placing files in the trash
+#[derive(Debug, Clone, Default)]
+pub struct PutOptions {
+    /// Paths that may not be placed in the trash, on top of the built-in protected paths and `OSCAR_PROTECTED_PATHS`
+    pub protected_paths: Vec<PathBuf>,
+
+    /// Skip the protected path checks entirely
+    pub allow_protected: bool,
+
+    /// Use the trash directory of this top directory instead of the one `trash_dir_for_path` picks
+    pub top_dir: Option<PathBuf>
+}
+
+pub fn trash_put<P: AsRef<Path>>(path: P) -> Result<()> {
+    trash_put_with_options(path, &PutOptions::default())
+}
+
+/// Places `path` in the trash. Protected paths (the file system root, the home directory, trash directories, mount points
+/// and the configured protected paths) are refused with a `ProtectedPathError` unless `options.allow_protected` is set.
+/// When `path` was copied into the trash but could not be fully removed afterwards, it stays in the trash and an
+/// `IncompleteMoveError` is returned.
+pub fn trash_put_with_options<P: AsRef<Path>>(path: P, options: &PutOptions) -> Result<()> {
+    trash_put_with_mover(path, options, |from, to| move_path(from, to))
+}
+
+/// Like `trash_put_with_options`, but moves `path` into the trash with `move_entry` instead of `move_path`
+pub fn trash_put_with_mover<P: AsRef<Path>, M: FnOnce(&Path, &Path) -> Result<()>>(path: P, options: &PutOptions, move_entry: M) -> Result<()> {
+    let path = path.as_ref();
     create_home_trash_dir_if_not_exists()?;
 
-    let os_absolute_path = canonicalize(path)?;
-    let os_path_exists = exists(&os_absolute_path)?;
+    // symlinks are trashed themselves, so neither the existence check nor the absolute path may follow them
+    let os_path_exists = symlink_metadata(path).is_ok();
 
     if os_path_exists {
-        match create_trash_info_entry(&os_absolute_path) {
-            Ok(_) => {
-                let trash_files_directory = freedesktop_home_trash_files_dir().unwrap();
-                match os_absolute_path.file_name() {
-                    Some(filename) => {
-                        match rename(path, trash_files_directory.join(filename)) {
-                            Ok(_) => Ok(()),
-                            Err(error) => Err(error)
-                        }
-                    },
-                    None => Err(Error::new(ErrorKind::InvalidInput, format!("Cannot place {} in trash", path)))
+        let os_absolute_path = absolute_path_without_following(path)?;
+        if !options.allow_protected {
+            check_not_protected(&os_absolute_path, &options.protected_paths)?;
+        }
+
+        let trash_dir = match &options.top_dir {
+            Some(top_dir) => {
+                let trash_dir = freedesktop_topdir_trash_dir(top_dir);
+                create_trash_dir_if_not_exists(&trash_dir)?;
+                trash_dir
+            },
+            None => trash_dir_for_path(&os_absolute_path)?
+        };
+
+        match create_trash_info_entry(&trash_dir, &os_absolute_path) {
+            Ok(trash_entry_name) => {
+                let trash_file_path = trash_files_dir(&trash_dir).join(&trash_entry_name);
+                let move_result = move_entry(path, &trash_file_path);
+
+                // roll back only when nothing made it into the trash, otherwise the trashinfo file would show up as
+                // an entry that can never be restored. A payload that did (e.g. a copy whose source could not be
+                // fully removed afterwards) keeps its trashinfo file, so it is never left unrestorable.
+                if move_result.is_err() && !exists_without_following(&trash_file_path).unwrap_or(true) {
+                    let _ = remove_file(with_trashinfo_extension(&trash_info_dir(&trash_dir).join(&trash_entry_name)));
+                    return move_result;
                 }
+
+                // the directorysizes cache is only an optimization, failing to update it does not fail the put
+                if trash_file_path.symlink_metadata().is_ok_and(|metadata| metadata.is_dir()) {
+                    let _ = add_directory_size(&trash_dir, &trash_entry_name);
+                }
+
+                move_result
             },
             Err(error) => Err(error)
         }
     } else {
-        Err(Error::new(ErrorKind::NotFound, format!("{} does not exist", path)))
+        Err(Error::new(ErrorKind::NotFound, format!("{} does not exist", path.display())))
     }
-}
\ No newline at end of file
+}
+
+/// Applies rm(1)'s rules for directories, for when oscar stands in for it: a directory may only be trashed
+/// when `recursive` is set, or when it is empty and `empty_dirs` is set
+pub fn check_rm_compatible(path: &Path, recursive: bool, empty_dirs: bool) -> Result<()> {
+    let metadata = symlink_metadata(path)?;
+
+    if metadata.is_dir() && !recursive {
+        if !empty_dirs {
+            return Err(Error::new(ErrorKind::IsADirectory, format!("{} is a directory", path.display())));
+        }
+
+        if read_dir(path)?.next().is_some() {
+            return Err(Error::new(ErrorKind::DirectoryNotEmpty, format!("{} is not empty", path.display())));
+        }
+    }
+
+    Ok(())
+}
+
+/// Places every path in `paths` in the trash, carrying on past failures.
+/// Returns the outcome for each path, in the order they were given.
+pub fn trash_put_many<P: AsRef<Path>>(paths: &[P], options: &PutOptions) -> Vec<(PathBuf, Result<()>)> {
+    paths.iter()
+        .map(|path| (path.as_ref().to_path_buf(), trash_put_with_options(path, options)))
+        .collect()
+}
@@ -1,6 +1,5 @@
 use std::{
     fs::{
-        exists,
         remove_dir_all,
         remove_file,
     }, 
@@ -11,22 +10,18 @@ use std::{
     },
 };
 
-use crate::{common::*, trash_info::TrashInfo};
+use crate::{common::*, directory_sizes::remove_directory_size, trash_info::TrashInfo};
 
 fn remove_trash_entry(trash_entry: &TrashInfo) -> Result<()> {
-    let (trash_info_dir, trash_files_dir) = (
-        freedesktop_home_trash_info_dir().unwrap(), 
-        freedesktop_home_trash_files_dir().unwrap()
-    );
+    let full_trash_info_path = trash_entry.trash_info_path();
+    let full_trash_item_path = trash_entry.trash_file_path();
 
-    let full_trash_info_path = with_trashinfo_extension(&trash_info_dir.join(&trash_entry.path));
-    let full_trash_item_path = trash_files_dir.join(&trash_entry.path);
-
-    let metadata = full_trash_item_path.metadata()?;
+    let metadata = full_trash_item_path.symlink_metadata()?;
 
     if metadata.is_dir() {
         remove_dir_all(&full_trash_item_path)?;
         remove_file(&full_trash_info_path)?;
+        let _ = remove_directory_size(&trash_entry.trash_dir, &trash_entry.path);
 
         Ok(())
     } else {
@@ -38,14 +33,19 @@ fn remove_trash_entry(trash_entry: &TrashInfo) -> Result<()> {
 }
 
 pub fn trash_remove(trash_entry: &TrashInfo) -> Result<()> {
-    create_home_trash_dir_if_not_exists()?;
-
-    let trash_files_dir = freedesktop_home_trash_files_dir().unwrap();
-    let full_trash_file_path = trash_files_dir.join(&trash_entry.path);
+    create_trash_dir_if_not_exists(&trash_entry.trash_dir)?;
 
-    match exists(full_trash_file_path) {
+    match exists_without_following(&trash_entry.trash_file_path()) {
         Ok(true) => remove_trash_entry(trash_entry),
-        Ok(false) => Err(Error::new(ErrorKind::NotFound, format!("{} is not in the trash", &trash_entry.path))),
+        Ok(false) => Err(Error::new(ErrorKind::NotFound, format!("{} is not in the trash", trash_entry.path.to_string_lossy()))),
         Err(error) => Err(error)
     }
-}
\ No newline at end of file
+}
+
+/// Permanently deletes several entries, carrying on past failures. Returns the result for every entry, in order.
+pub fn trash_remove_many(trash_entries: &[TrashInfo]) -> Vec<(&TrashInfo, Result<()>)> {
+    trash_entries
+        .iter()
+        .map(|trash_entry| (trash_entry, trash_remove(trash_entry)))
+        .collect()
+}
@@ -1,43 +1,330 @@
 use std::{
-    fs::{exists, remove_file, rename}, 
-    io::{Error, ErrorKind, Result}
+    error::Error as StdError,
+    fmt::Display,
+    fs::{create_dir, read_dir, remove_dir, remove_file}, 
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+    str::FromStr
+};
+use crate::{
+    actions::trash_put::trash_put,
+    common::*,
+    directory_sizes::remove_directory_size,
+    file_move::move_path,
+    trash_info::TrashInfo
 };
-use crate::{common::*, trash_info::TrashInfo};
 
-fn restore_from_trash(trash_entry: &TrashInfo) -> Result<()> {
-    let (trash_info_dir, trash_files_dir) = (
-        freedesktop_home_trash_info_dir().unwrap(), 
-        freedesktop_home_trash_files_dir().unwrap()
-    );
+/// What to do when something already exists where an entry is restored to
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ConflictStrategy {
+    /// Fail with an error
+    #[default]
+    Fail,
+    /// Place the existing file in the trash, then restore
+    Overwrite,
+    /// Restore next to the existing file, as `name (restored).ext`
+    Rename,
+    /// Leave the entry in the trash
+    Skip,
+    /// Move the contents of a trashed directory into the existing directory, handling conflicting files
+    /// with `RestoreOptions::on_file_conflict`. Other conflicts are handled like a conflicting file.
+    Merge
+}
 
-    let full_trash_info_path = with_trashinfo_extension(&trash_info_dir.join(&trash_entry.path));
-    let full_trash_item_path = trash_files_dir.join(&trash_entry.path);
+impl FromStr for ConflictStrategy {
+    type Err = String;
 
-    rename(&full_trash_item_path, &trash_entry.full_path)?;
-    remove_file(full_trash_info_path)?;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "fail" => Ok(ConflictStrategy::Fail),
+            "overwrite" => Ok(ConflictStrategy::Overwrite),
+            "rename" => Ok(ConflictStrategy::Rename),
+            "skip" => Ok(ConflictStrategy::Skip),
+            "merge" => Ok(ConflictStrategy::Merge),
+            _ => Err(format!("unknown conflict strategy `{s}`, expected one of fail, overwrite, rename, skip, merge"))
+        }
+    }
+}
 
-    Ok(())
+/// Options for `trash_restore_to`
+#[derive(Debug, Clone)]
+pub struct RestoreOptions {
+    /// What to do when the destination already exists
+    pub on_conflict: ConflictStrategy,
+
+    /// What to do with conflicting files while merging directories. Merging files is not possible, so `Merge` renames.
+    pub on_file_conflict: ConflictStrategy,
+
+    /// Recreate the directories leading to the destination when they no longer exist
+    pub create_parents: bool
 }
 
-pub fn trash_restore(trash_entry: &TrashInfo, overwrite: bool) -> Result<()> {
-    create_home_trash_dir_if_not_exists()?;
+impl Default for RestoreOptions {
+    fn default() -> Self {
+        RestoreOptions {
+            on_conflict: ConflictStrategy::Fail,
+            on_file_conflict: ConflictStrategy::Rename,
+            create_parents: false
+        }
+    }
+}
 
-    let trash_files_dir = freedesktop_home_trash_files_dir().unwrap();
-    let full_trash_file_path = trash_files_dir.join(&trash_entry.path);
+/// How a restore went
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestoreOutcome {
+    /// Restored to a destination that did not exist
+    Restored,
+    /// Restored under another name, as the destination was taken
+    Renamed,
+    /// The file at the destination was placed in the trash first
+    Overwritten,
+    /// The contents of the directory were moved into the existing directory
+    Merged,
+    /// Merged, but some conflicting items were skipped and are still in the trash
+    PartiallyMerged,
+    /// Left in the trash, as the destination was taken
+    Skipped
+}
 
-    let file_exists_in_trash = exists(full_trash_file_path)?;
-    if file_exists_in_trash {
-        let does_full_path_exist = exists(&trash_entry.full_path)?;
-        if does_full_path_exist {
-            if overwrite {
-                restore_from_trash(trash_entry)
+/// What a restore did
+#[derive(Debug, PartialEq)]
+pub struct RestoreReport {
+    /// Where the entry was restored to
+    pub destination: PathBuf,
+
+    /// The directories that were recreated for it, outermost first
+    pub created_dirs: Vec<PathBuf>,
+
+    pub outcome: RestoreOutcome
+}
+
+/// Returned (wrapped in an `std::io::Error` of kind `NotFound`) when the directory an entry would be restored to
+/// no longer exists and `RestoreOptions::create_parents` is not set. Use `MissingParentError::from_io_error` to get it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingParentError {
+    pub destination: PathBuf,
+
+    /// The directories that would have to be created, outermost first
+    pub missing_dirs: Vec<PathBuf>
+}
+
+impl MissingParentError {
+    /// Gets the `MissingParentError` an I/O error was created from, if any
+    pub fn from_io_error(error: &Error) -> Option<&MissingParentError> {
+        error.get_ref().and_then(|inner| inner.downcast_ref::<MissingParentError>())
+    }
+}
+
+impl Display for MissingParentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.destination.parent() {
+            Some(parent) => write!(f, "cannot restore {}: the directory {} no longer exists", self.destination.display(), parent.display()),
+            None => write!(f, "cannot restore {}: its directory no longer exists", self.destination.display())
+        }
+    }
+}
+
+impl StdError for MissingParentError {}
+
+/// The ancestors of `path` that do not exist, outermost first
+fn missing_parent_dirs(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut missing_dirs = vec![];
+
+    for ancestor in path.ancestors().skip(1) {
+        if ancestor.as_os_str().is_empty() || exists_without_following(ancestor)? {
+            break;
+        }
+        missing_dirs.push(ancestor.to_path_buf());
+    }
+    missing_dirs.reverse();
+
+    Ok(missing_dirs)
+}
+
+fn restore_from_trash(trash_entry: &TrashInfo, destination: &Path, options: &RestoreOptions, outcome: RestoreOutcome) -> Result<RestoreReport> {
+    let missing_dirs = missing_parent_dirs(destination)?;
+    if !missing_dirs.is_empty() && !options.create_parents {
+        return Err(Error::new(ErrorKind::NotFound, MissingParentError { destination: destination.to_path_buf(), missing_dirs }));
+    }
+
+    for missing_dir in &missing_dirs {
+        create_dir(missing_dir)?;
+    }
+
+    move_path(trash_entry.trash_file_path(), destination)?;
+    remove_file(trash_entry.trash_info_path())?;
+    let _ = remove_directory_size(&trash_entry.trash_dir, &trash_entry.path);
+
+    Ok(RestoreReport { destination: destination.to_path_buf(), created_dirs: missing_dirs, outcome })
+}
+
+/// Builds the n-th name to restore `path` under when it is taken: `notes (restored).txt`, `notes (restored 2).txt`...
+fn restored_name_candidate(path: &Path, n: usize) -> PathBuf {
+    let file_name = path.file_name().unwrap_or(path.as_os_str());
+    let mut candidate = Path::new(file_name).file_stem().unwrap_or(file_name).to_owned();
+
+    if n == 1 {
+        candidate.push(" (restored)");
+    } else {
+        candidate.push(format!(" (restored {n})"));
+    }
+
+    if let Some(extension) = Path::new(file_name).extension() {
+        candidate.push(".");
+        candidate.push(extension);
+    }
+
+    path.with_file_name(candidate)
+}
+
+/// The first `name (restored).ext`-style path next to `path` that does not exist yet
+pub fn unique_restore_destination(path: &Path) -> Result<PathBuf> {
+    for n in 1.. {
+        let candidate = restored_name_candidate(path, n);
+        if !exists_without_following(&candidate)? {
+            return Ok(candidate);
+        }
+    }
+
+    unreachable!()
+}
+
+fn already_exists_error(destination: &Path) -> Error {
+    Error::new(ErrorKind::PermissionDenied, format!("{} already exists", destination.display()))
+}
+
+/// The first item of the directory `from` that would conflict with an item of the directory `to` when merging them
+fn first_merge_conflict(from: &Path, to: &Path) -> Result<Option<PathBuf>> {
+    for entry in read_dir(from)? {
+        let source = entry?.path();
+        let destination = to.join(source.file_name().unwrap_or_default());
+
+        if !exists_without_following(&destination)? {
+            continue;
+        } else if is_dir_without_following(&source) && is_dir_without_following(&destination) {
+            if let Some(conflict) = first_merge_conflict(&source, &destination)? {
+                return Ok(Some(conflict));
+            }
+        } else {
+            return Ok(Some(destination));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Moves the contents of the directory `from` into the directory `to`, merging subdirectories that exist in both.
+/// Returns whether everything was moved, in which case `from` is removed.
+fn merge_dirs(from: &Path, to: &Path, on_file_conflict: ConflictStrategy) -> Result<bool> {
+    let mut merged_everything = true;
+
+    for entry in read_dir(from)? {
+        let source = entry?.path();
+        let destination = to.join(source.file_name().unwrap_or_default());
+
+        if !exists_without_following(&destination)? {
+            move_path(&source, &destination)?;
+        } else if is_dir_without_following(&source) && is_dir_without_following(&destination) {
+            merged_everything &= merge_dirs(&source, &destination, on_file_conflict)?;
+        } else {
+            match on_file_conflict {
+                ConflictStrategy::Fail => return Err(already_exists_error(&destination)),
+                ConflictStrategy::Skip => merged_everything = false,
+                ConflictStrategy::Rename | ConflictStrategy::Merge => move_path(&source, &unique_restore_destination(&destination)?)?,
+                ConflictStrategy::Overwrite => {
+                    trash_put(&destination)?;
+                    move_path(&source, &destination)?;
+                }
+            }
+        }
+    }
+
+    if merged_everything {
+        remove_dir(from)?;
+    }
+
+    Ok(merged_everything)
+}
+
+/// Resolves a conflict at `destination` according to `strategy`
+fn restore_with_conflict(trash_entry: &TrashInfo, destination: &Path, options: &RestoreOptions, strategy: ConflictStrategy) -> Result<RestoreReport> {
+    match strategy {
+        ConflictStrategy::Fail => Err(already_exists_error(destination)),
+        ConflictStrategy::Skip => Ok(RestoreReport { destination: destination.to_path_buf(), created_dirs: vec![], outcome: RestoreOutcome::Skipped }),
+        ConflictStrategy::Rename => restore_from_trash(trash_entry, &unique_restore_destination(destination)?, options, RestoreOutcome::Renamed),
+        ConflictStrategy::Overwrite => {
+            // the existing file is trashed rather than deleted, so nothing is ever lost
+            trash_put(destination)?;
+            restore_from_trash(trash_entry, destination, options, RestoreOutcome::Overwritten)
+        },
+        ConflictStrategy::Merge if is_dir_without_following(&trash_entry.trash_file_path()) && is_dir_without_following(destination) => {
+            // fail before anything is moved, rather than halfway through the merge
+            if options.on_file_conflict == ConflictStrategy::Fail {
+                if let Some(conflict) = first_merge_conflict(&trash_entry.trash_file_path(), destination)? {
+                    return Err(already_exists_error(&conflict));
+                }
+            }
+
+            // the directory changes even when the merge fails partway, so its cached size is dropped either way
+            let merge_result = merge_dirs(&trash_entry.trash_file_path(), destination, options.on_file_conflict);
+            let _ = remove_directory_size(&trash_entry.trash_dir, &trash_entry.path);
+            let merged_everything = merge_result?;
+
+            if merged_everything {
+                remove_file(trash_entry.trash_info_path())?;
+                Ok(RestoreReport { destination: destination.to_path_buf(), created_dirs: vec![], outcome: RestoreOutcome::Merged })
             } else {
-                Err(Error::new(ErrorKind::PermissionDenied, format!("{} already exists", &trash_entry.full_path)))
+                Ok(RestoreReport { destination: destination.to_path_buf(), created_dirs: vec![], outcome: RestoreOutcome::PartiallyMerged })
             }
+        },
+        ConflictStrategy::Merge => match options.on_file_conflict {
+            ConflictStrategy::Merge => restore_with_conflict(trash_entry, destination, options, ConflictStrategy::Rename),
+            on_file_conflict => restore_with_conflict(trash_entry, destination, options, on_file_conflict)
+        }
+    }
+}
+
+/// Where an entry restored to `to` ends up: inside `to` under its original name when `to` is an existing directory,
+/// otherwise at `to` itself
+pub fn restore_destination(trash_entry: &TrashInfo, to: &Path) -> PathBuf {
+    match trash_entry.full_path.file_name() {
+        Some(file_name) if to.is_dir() => to.join(file_name),
+        _ => to.to_path_buf()
+    }
+}
+
+pub fn trash_restore(trash_entry: &TrashInfo, overwrite: bool) -> Result<()> {
+    let on_conflict = if overwrite { ConflictStrategy::Overwrite } else { ConflictStrategy::Fail };
+    trash_restore_to(trash_entry, &trash_entry.full_path, &RestoreOptions { on_conflict, ..RestoreOptions::default() })?;
+
+    Ok(())
+}
+
+/// Restores an entry to `destination`, which is usually its original location
+pub fn trash_restore_to(trash_entry: &TrashInfo, destination: &Path, options: &RestoreOptions) -> Result<RestoreReport> {
+    create_trash_dir_if_not_exists(&trash_entry.trash_dir)?;
+
+    let file_exists_in_trash = exists_without_following(&trash_entry.trash_file_path())?;
+    if file_exists_in_trash {
+        let does_destination_exist = exists_without_following(destination)?;
+        if does_destination_exist {
+            restore_with_conflict(trash_entry, destination, options, options.on_conflict)
         } else {
-            restore_from_trash(trash_entry)
+            restore_from_trash(trash_entry, destination, options, RestoreOutcome::Restored)
         }
     } else {
-        restore_from_trash(trash_entry)
+        restore_from_trash(trash_entry, destination, options, RestoreOutcome::Restored)
     }
+}
+
+/// The entries to offer when picking one to restore, most recently deleted first. Like trash-cli's trash-restore,
+/// only entries deleted from `dir` or its subdirectories are offered when `dir` is given.
+pub fn restore_candidates(trash_contents: Vec<TrashInfo>, dir: Option<&Path>) -> Vec<TrashInfo> {
+    let mut candidates: Vec<TrashInfo> = match dir {
+        Some(dir) => trash_contents.into_iter().filter(|trash_info| trash_info.full_path.starts_with(dir) && trash_info.full_path != dir).collect(),
+        None => trash_contents
+    };
+    candidates.sort_by_key(|trash_info| std::cmp::Reverse(trash_info.deletion_date));
+
+    candidates
 }
\ No newline at end of file
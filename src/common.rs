@@ -1,11 +1,13 @@
 use std::{
     env::var,
-    fs::{create_dir_all, exists, read_dir}, 
-    io::{Error, Result}, 
-    path::{Path, PathBuf}
+    fs::{canonicalize, create_dir_all, exists, read_dir, symlink_metadata, DirBuilder}, 
+    io::{Error, ErrorKind, Result}, 
+    os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt},
+    path::{absolute, Path, PathBuf}
 };
 
 use crate::constants::TRASH_INFO_FILE_EXTENSION;
+use crate::mount_table::{current_mount_table, find_mount, MountEntry};
 use crate::trash_info::TrashInfo;
 
 /// This function gets the home trash directory as defined in the Freedesktop.org spec: https://specifications.freedesktop.org/trash-spec/latest/
@@ -22,24 +24,225 @@ pub fn freedesktop_home_trash_dir() -> Option<PathBuf> {
 }
 
 pub fn freedesktop_home_trash_files_dir() -> Option<PathBuf> {
-    match freedesktop_home_trash_dir() {
-        Some(home_trash_dir) => Some(home_trash_dir.join("files")),
-        None => None
-    }
+    freedesktop_home_trash_dir().map(|home_trash_dir| trash_files_dir(&home_trash_dir))
 }
 
 pub fn freedesktop_home_trash_info_dir() -> Option<PathBuf> {
-    match freedesktop_home_trash_dir() {
-        Some(home_trash_dir) => Some(home_trash_dir.join("info")),
-        None => None
+    freedesktop_home_trash_dir().map(|home_trash_dir| trash_info_dir(&home_trash_dir))
+}
+
+pub fn trash_files_dir(trash_dir: &Path) -> PathBuf {
+    trash_dir.join("files")
+}
+
+pub fn trash_info_dir(trash_dir: &Path) -> PathBuf {
+    trash_dir.join("info")
+}
+
+/// Makes `path` absolute, resolving symlinks in its parent directories but not in its last component,
+/// so that a symlink refers to the link itself rather than to its target
+pub fn absolute_path_without_following(path: &Path) -> Result<PathBuf> {
+    let absolute_path = absolute(path)?;
+
+    match (absolute_path.parent(), absolute_path.file_name()) {
+        (Some(parent), Some(filename)) => Ok(canonicalize(parent)?.join(filename)),
+        _ => Err(Error::new(ErrorKind::InvalidInput, format!("{} does not name a file or directory", path.display())))
+    }
+}
+
+/// Like `std::fs::exists`, but does not follow a symlink in the last component, so dangling symlinks exist too
+pub fn exists_without_following(path: &Path) -> Result<bool> {
+    match symlink_metadata(path) {
+        Ok(_) => Ok(true),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(error)
     }
 }
 
-pub fn with_trashinfo_extension(p: &PathBuf) -> PathBuf {
-    p.with_extension(match p.extension() {
-        Some(extension) => format!("{}.{}", extension.to_str().unwrap(), TRASH_INFO_FILE_EXTENSION),
-        None => String::from(TRASH_INFO_FILE_EXTENSION)
-    })
+/// Whether `path` is a directory, without following a symlink in its last component
+pub fn is_dir_without_following(path: &Path) -> bool {
+    symlink_metadata(path).is_ok_and(|metadata| metadata.is_dir())
+}
+
+pub fn current_uid() -> u32 {
+    // SAFETY: getuid is always successful and has no side effects
+    unsafe { libc::getuid() }
+}
+
+/// Checks that `$topdir/.Trash` may be used as a shared trash directory: it must be a real directory (not a symlink)
+/// with the sticky bit set
+fn is_valid_shared_trash_dir(shared_trash_dir: &Path) -> bool {
+    const STICKY_BIT: u32 = 0o1000;
+
+    match symlink_metadata(shared_trash_dir) {
+        Ok(metadata) => metadata.is_dir() && metadata.permissions().mode() & STICKY_BIT != 0,
+        Err(_) => false
+    }
+}
+
+/// The trash directory a user gets in a shared `$topdir/.Trash` directory, i.e. `$topdir/.Trash/$uid`
+pub fn freedesktop_topdir_shared_trash_dir(top_dir: &Path) -> PathBuf {
+    top_dir.join(".Trash").join(current_uid().to_string())
+}
+
+/// The per-user trash directory of a top directory, i.e. `$topdir/.Trash-$uid`
+pub fn freedesktop_topdir_user_trash_dir(top_dir: &Path) -> PathBuf {
+    top_dir.join(format!(".Trash-{}", current_uid()))
+}
+
+/// This function gets the trash directory files on `top_dir` should be placed in, as defined in the Freedesktop.org spec.
+/// `$topdir/.Trash/$uid` is preferred when `$topdir/.Trash` passes the spec's checks, otherwise `$topdir/.Trash-$uid` is used.
+pub fn freedesktop_topdir_trash_dir(top_dir: &Path) -> PathBuf {
+    if is_valid_shared_trash_dir(&top_dir.join(".Trash")) {
+        freedesktop_topdir_shared_trash_dir(top_dir)
+    } else {
+        freedesktop_topdir_user_trash_dir(top_dir)
+    }
+}
+
+/// The top directory a trash directory belongs to, i.e. `$topdir` for `$topdir/.Trash/$uid` and `$topdir/.Trash-$uid`.
+/// Returns `None` for the home trash, whose trashinfo files always hold absolute paths.
+pub fn trash_dir_top_dir(trash_dir: &Path) -> Option<PathBuf> {
+    if freedesktop_home_trash_dir().is_some_and(|home_trash_dir| home_trash_dir == trash_dir) {
+        return None;
+    }
+
+    let trash_dir_name = trash_dir.file_name()?;
+    if trash_dir_name == format!(".Trash-{}", current_uid()).as_str() {
+        trash_dir.parent().map(Path::to_path_buf)
+    } else if trash_dir_name == current_uid().to_string().as_str() && trash_dir.parent()?.file_name()? == ".Trash" {
+        trash_dir.parent()?.parent().map(Path::to_path_buf)
+    } else {
+        None
+    }
+}
+
+/// Every trash directory of `top_dir` that currently exists and may be read from. Like `$topdir/.Trash`, trash
+/// directories that are symlinks are not trusted.
+pub fn existing_topdir_trash_dirs(top_dir: &Path) -> Vec<PathBuf> {
+    let mut trash_dirs = vec![];
+
+    if is_valid_shared_trash_dir(&top_dir.join(".Trash")) {
+        let shared_trash_dir = freedesktop_topdir_shared_trash_dir(top_dir);
+        if is_dir_without_following(&shared_trash_dir) {
+            trash_dirs.push(shared_trash_dir);
+        }
+    }
+
+    let user_trash_dir = freedesktop_topdir_user_trash_dir(top_dir);
+    if is_dir_without_following(&user_trash_dir) {
+        trash_dirs.push(user_trash_dir);
+    }
+
+    trash_dirs
+}
+
+/// Probes every mount for top directory trashes that currently exist
+pub fn topdir_trash_dirs(mounts: &[MountEntry]) -> Vec<PathBuf> {
+    let mut trash_dirs: Vec<PathBuf> = vec![];
+
+    for mount in mounts {
+        for trash_dir in existing_topdir_trash_dirs(&mount.mount_point) {
+            if !trash_dirs.contains(&trash_dir) {
+                trash_dirs.push(trash_dir);
+            }
+        }
+    }
+
+    trash_dirs
+}
+
+/// Finds the mount point of the file system `path` lives on. The mount table is consulted first; if it is unavailable
+/// the mount point is found by walking up the ancestors of `path` until the device changes
+pub fn find_mount_point(path: &Path) -> Result<PathBuf> {
+    let device = symlink_metadata(path)?.dev();
+
+    if let Ok(mounts) = current_mount_table() {
+        if let Some(mount) = find_mount(&mounts, path, device) {
+            return Ok(mount.mount_point.clone());
+        }
+    }
+
+    let mut mount_point = path;
+
+    while let Some(parent) = mount_point.parent() {
+        if symlink_metadata(parent)?.dev() != device {
+            break;
+        }
+        mount_point = parent;
+    }
+
+    Ok(mount_point.to_path_buf())
+}
+
+/// Picks the trash directory `path` should be placed in: the home trash when it lives on the same file system,
+/// otherwise the trash directory of the top directory of its mount. When no top directory trash can be created
+/// (e.g. a read-only or foreign-owned mount root) an error naming the top directory is returned, rather than copying
+/// the file across file systems to the home trash.
+pub fn trash_dir_for_path(path: &Path) -> Result<PathBuf> {
+    create_home_trash_dir_if_not_exists()?;
+
+    let home_trash_dir = freedesktop_home_trash_dir().unwrap();
+    if symlink_metadata(&home_trash_dir)?.dev() == symlink_metadata(path)?.dev() {
+        return Ok(home_trash_dir);
+    }
+
+    let top_dir = find_mount_point(path)?;
+    let topdir_trash_dir = freedesktop_topdir_trash_dir(&top_dir);
+
+    // per the spec, $topdir/.Trash-$uid is the fallback for a $topdir/.Trash/$uid that cannot be created
+    let mut last_error = None;
+    for trash_dir in [topdir_trash_dir, freedesktop_topdir_user_trash_dir(&top_dir)] {
+        match create_trash_dir_if_not_exists(&trash_dir) {
+            Ok(_) => return Ok(trash_dir),
+            Err(error) => last_error = Some(error)
+        }
+    }
+
+    let error = last_error.unwrap_or_else(|| Error::from(ErrorKind::PermissionDenied));
+    Err(Error::new(error.kind(), format!("cannot create a trash directory in {}: {}", top_dir.display(), error)))
+}
+
+/// The home trash followed by the trash directories found on `mounts`
+pub fn all_trash_dirs_in(mounts: &[MountEntry]) -> Vec<PathBuf> {
+    let mut trash_dirs: Vec<PathBuf> = freedesktop_home_trash_dir().into_iter().collect();
+
+    for trash_dir in topdir_trash_dirs(mounts) {
+        if !trash_dirs.contains(&trash_dir) {
+            trash_dirs.push(trash_dir);
+        }
+    }
+
+    trash_dirs
+}
+
+/// The home trash followed by the trash directories of every mounted file system
+pub fn all_trash_dirs() -> Vec<PathBuf> {
+    all_trash_dirs_in(&current_mount_table().unwrap_or_default())
+}
+
+/// Creates the `files` and `info` directories of `trash_dir`. Top directory trashes are created readable by their owner only,
+/// as required by the spec. Returns whether anything had to be created.
+pub fn create_trash_dir_if_not_exists(trash_dir: &Path) -> Result<bool> {
+    let mut was_created = false;
+
+    for dir in [trash_files_dir(trash_dir), trash_info_dir(trash_dir)] {
+        if !exists(&dir)? {
+            DirBuilder::new().recursive(true).mode(0o700).create(&dir)?;
+            was_created = true;
+        }
+    }
+
+    Ok(was_created)
+}
+
+/// Appends the `.trashinfo` extension to `p`, keeping any extension it already has (`test.txt` -> `test.txt.trashinfo`)
+pub fn with_trashinfo_extension(p: &Path) -> PathBuf {
+    let mut trash_info_path = p.as_os_str().to_owned();
+    trash_info_path.push(".");
+    trash_info_path.push(TRASH_INFO_FILE_EXTENSION);
+
+    PathBuf::from(trash_info_path)
 }
 
 pub fn create_home_trash_info_dir_if_not_exists() -> Result<bool>{
@@ -54,12 +257,7 @@ pub fn create_home_trash_info_dir_if_not_exists() -> Result<bool>{
         };
     }
 
-    return Err(
-        Error::new(
-            std::io::ErrorKind::Other, 
-            "Unable to determine the path for the home trash info directory."
-        )
-    );
+    Err(Error::other("Unable to determine the path for the home trash info directory."))
 }
 
 pub fn create_home_trash_files_dir_if_not_exists() -> Result<bool>{
@@ -74,12 +272,7 @@ pub fn create_home_trash_files_dir_if_not_exists() -> Result<bool>{
         };
     }
 
-    return Err(
-        Error::new(
-            std::io::ErrorKind::Other, 
-            "Unable to determine the path for the home trash files directory."
-        )
-    );
+    Err(Error::other("Unable to determine the path for the home trash files directory."))
 }
 
 pub fn create_home_trash_dir_if_not_exists() -> Result<bool> {
@@ -94,26 +287,47 @@ pub fn create_home_trash_dir_if_not_exists() -> Result<bool> {
     }
 }
 
-pub fn get_home_trash_contents() -> Result<Vec<TrashInfo>> {
-    if let Some(info_path) = freedesktop_home_trash_info_dir() {
-        let mut trash_contents = vec![];
-
-        for entry in read_dir(info_path)? {
-            let path = entry?.path();
-            if path.is_file() {
-                if let Ok(trash_info) = TrashInfo::from_file(path) {
-                    trash_contents.push(trash_info);
-                }
+pub fn get_trash_contents(trash_dir: &Path) -> Result<Vec<TrashInfo>> {
+    let mut trash_contents = vec![];
+
+    for entry in read_dir(trash_info_dir(trash_dir))? {
+        let path = entry?.path();
+        if path.is_file() {
+            if let Ok(trash_info) = TrashInfo::from_file(path) {
+                trash_contents.push(trash_info);
             }
         }
+    }
+
+    Ok(trash_contents)
+}
 
-        Ok(trash_contents)
+pub fn get_home_trash_contents() -> Result<Vec<TrashInfo>> {
+    if let Some(home_trash_dir) = freedesktop_home_trash_dir() {
+        get_trash_contents(&home_trash_dir)
     } else {
-        Err(
-            Error::new(
-                std::io::ErrorKind::Other, 
-                "Unable to determine the path for the home trash directory."
-            )
-        )
+        Err(Error::other("Unable to determine the path for the home trash directory."))
+    }
+}
+
+/// Contents of the home trash and of every top directory trash on `mounts`, each entry tagged with the trash directory
+/// it came from. Trash directories that cannot be read are skipped.
+pub fn get_all_trash_contents_in(mounts: &[MountEntry]) -> Result<Vec<TrashInfo>> {
+    create_home_trash_dir_if_not_exists()?;
+
+    let mut trash_contents = vec![];
+    for trash_dir in all_trash_dirs_in(mounts) {
+        match get_trash_contents(&trash_dir) {
+            Ok(contents) => trash_contents.extend(contents),
+            Err(error) if error.kind() == ErrorKind::NotFound || error.kind() == ErrorKind::PermissionDenied => (),
+            Err(error) => return Err(error)
+        }
     }
+
+    Ok(trash_contents)
+}
+
+/// Contents of the home trash and of every top directory trash of the currently mounted file systems
+pub fn get_all_trash_contents() -> Result<Vec<TrashInfo>> {
+    get_all_trash_contents_in(&current_mount_table().unwrap_or_default())
 }
\ No newline at end of file
@@ -1,4 +1,7 @@
 pub static TRASH_INFO_SECTION_HEADER: &str = "Trash Info";
 pub static TRASH_INFO_PATH_KEY: &str = "Path";
 pub static TRASH_INFO_DELETION_DATE_KEY: &str = "DeletionDate";
-pub static TRASH_INFO_FILE_EXTENSION: &str = "trashinfo";
\ No newline at end of file
+pub static TRASH_INFO_FILE_EXTENSION: &str = "trashinfo";
+pub static PROC_SELF_MOUNTINFO: &str = "/proc/self/mountinfo";
+pub static OSCAR_PROTECTED_PATHS_VAR: &str = "OSCAR_PROTECTED_PATHS";
+pub static DIRECTORY_SIZES_FILE: &str = "directorysizes";
@@ -0,0 +1,161 @@
+use std::{
+    ffi::{OsStr, OsString},
+    fs::{read_dir, read_to_string, rename, symlink_metadata, write},
+    io::{ErrorKind, Result},
+    os::unix::{ffi::{OsStrExt, OsStringExt}, fs::MetadataExt},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    common::{trash_files_dir, trash_info_dir, with_trashinfo_extension},
+    constants::DIRECTORY_SIZES_FILE,
+    string_encode::{decode_filename, encode_filename},
+    trash_info::TrashInfo,
+};
+
+/// A line of a trash directory's `directorysizes` cache
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectorySize {
+    /// Disk usage of the trashed directory in bytes
+    pub size: u64,
+
+    /// Modification time of the entry's trashinfo file in seconds since the epoch, used to tell whether the line is stale
+    pub trash_info_mtime: i64,
+
+    /// The name of the trashed directory in `files`
+    pub name: OsString
+}
+
+/// Location of the `directorysizes` cache of `trash_dir`
+pub fn directory_sizes_path(trash_dir: &Path) -> PathBuf {
+    trash_dir.join(DIRECTORY_SIZES_FILE)
+}
+
+fn parse_directory_size(line: &str) -> Option<DirectorySize> {
+    // size mtime percent-encoded-name
+    let mut fields = line.splitn(3, ' ');
+
+    Some(DirectorySize {
+        size: fields.next()?.parse().ok()?,
+        trash_info_mtime: fields.next()?.parse().ok()?,
+        name: OsString::from_vec(decode_filename(fields.next()?))
+    })
+}
+
+/// Parses the contents of a `directorysizes` file. Malformed lines are skipped.
+pub fn parse_directory_sizes(contents: &str) -> Vec<DirectorySize> {
+    contents.lines().filter_map(parse_directory_size).collect()
+}
+
+/// Reads the `directorysizes` cache of `trash_dir`, a missing cache is empty
+pub fn read_directory_sizes(trash_dir: &Path) -> Result<Vec<DirectorySize>> {
+    match read_to_string(directory_sizes_path(trash_dir)) {
+        Ok(contents) => Ok(parse_directory_sizes(&contents)),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(vec![]),
+        Err(error) => Err(error)
+    }
+}
+
+/// Replaces the `directorysizes` cache of `trash_dir`. The new contents are written next to the cache and renamed over it,
+/// so readers never see a half written file.
+fn write_directory_sizes(trash_dir: &Path, directory_sizes: &[DirectorySize]) -> Result<()> {
+    let contents: String = directory_sizes
+        .iter()
+        .map(|directory_size| format!(
+            "{} {} {}\n",
+            directory_size.size,
+            directory_size.trash_info_mtime,
+            encode_filename(directory_size.name.as_bytes())
+        ))
+        .collect();
+
+    let temporary_path = trash_dir.join(format!(".{DIRECTORY_SIZES_FILE}.{}", std::process::id()));
+    write(&temporary_path, contents)?;
+    rename(temporary_path, directory_sizes_path(trash_dir))
+}
+
+/// Disk usage of `path` in bytes, in the same way as `du -B1` counts it. Symlinks are not followed.
+pub fn disk_usage(path: &Path) -> Result<u64> {
+    let metadata = symlink_metadata(path)?;
+    let mut size = metadata.blocks() * 512;
+
+    if metadata.is_dir() {
+        for entry in read_dir(path)? {
+            size += disk_usage(&entry?.path())?;
+        }
+    }
+
+    Ok(size)
+}
+
+/// Number of files, directories and symlinks inside the directory `path`, counted recursively. Symlinks are not followed.
+pub fn count_items(path: &Path) -> Result<u64> {
+    let mut count = 0;
+
+    for entry in read_dir(path)? {
+        let entry = entry?;
+        count += 1;
+
+        if entry.file_type()?.is_dir() {
+            count += count_items(&entry.path())?;
+        }
+    }
+
+    Ok(count)
+}
+
+fn trash_info_mtime(trash_dir: &Path, name: &OsStr) -> Result<i64> {
+    Ok(symlink_metadata(with_trashinfo_extension(&trash_info_dir(trash_dir).join(name)))?.mtime())
+}
+
+/// Records the size of the directory `name` that was just placed in `trash_dir`
+pub fn add_directory_size(trash_dir: &Path, name: &OsStr) -> Result<()> {
+    let directory_size = DirectorySize {
+        size: disk_usage(&trash_files_dir(trash_dir).join(name))?,
+        trash_info_mtime: trash_info_mtime(trash_dir, name)?,
+        name: name.to_owned()
+    };
+
+    let mut directory_sizes = read_directory_sizes(trash_dir)?;
+    directory_sizes.retain(|existing| existing.name != directory_size.name);
+    directory_sizes.push(directory_size);
+
+    write_directory_sizes(trash_dir, &directory_sizes)
+}
+
+/// Drops the line for `name` from the cache of `trash_dir`, after it was removed from or restored out of the trash
+pub fn remove_directory_size(trash_dir: &Path, name: &OsStr) -> Result<()> {
+    let mut directory_sizes = read_directory_sizes(trash_dir)?;
+    let count = directory_sizes.len();
+    directory_sizes.retain(|directory_size| directory_size.name != name);
+
+    if directory_sizes.len() == count {
+        Ok(())
+    } else {
+        write_directory_sizes(trash_dir, &directory_sizes)
+    }
+}
+
+/// Size of a trashed file or directory in bytes, without walking directories: the size of a directory is only known
+/// when it has a fresh line in the `directorysizes` cache, otherwise `None` is returned.
+pub fn cached_trash_entry_size(trash_entry: &TrashInfo) -> Result<Option<u64>> {
+    let trash_file_path = trash_entry.trash_file_path();
+    if !symlink_metadata(&trash_file_path)?.is_dir() {
+        return disk_usage(&trash_file_path).map(Some);
+    }
+
+    let trash_info_mtime = trash_info_mtime(&trash_entry.trash_dir, &trash_entry.path)?;
+    Ok(read_directory_sizes(&trash_entry.trash_dir)?
+        .into_iter()
+        .find(|directory_size| directory_size.name == trash_entry.path && directory_size.trash_info_mtime == trash_info_mtime)
+        .map(|directory_size| directory_size.size))
+}
+
+/// Size of a trashed file or directory in bytes. Directories are looked up in the `directorysizes` cache first,
+/// and only walked when they are missing from it or their line is stale.
+pub fn trash_entry_size(trash_entry: &TrashInfo) -> Result<u64> {
+    match cached_trash_entry_size(trash_entry)? {
+        Some(size) => Ok(size),
+        None => disk_usage(&trash_entry.trash_file_path())
+    }
+}
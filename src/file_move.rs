@@ -0,0 +1,193 @@
+use std::{
+    error::Error as StdError,
+    ffi::{OsStr, OsString},
+    fmt::Display,
+    fs::{
+        create_dir,
+        read_dir,
+        read_link,
+        remove_dir_all,
+        remove_file,
+        rename,
+        set_permissions,
+        symlink_metadata,
+        File,
+        FileTimes,
+        OpenOptions,
+    },
+    io::{copy, Error, ErrorKind, Result},
+    os::unix::{ffi::OsStrExt, fs::symlink},
+    path::{Path, PathBuf},
+};
+
+static PARTIAL_COPY_MARKER: &str = ".oscar-partial-";
+
+/// Name of the temporary sibling a cross file system copy is written to before being moved into place:
+/// `.<name>.oscar-partial-<pid of the copying process>`
+fn partial_copy_path(to: &Path) -> Result<PathBuf> {
+    match (to.parent(), to.file_name()) {
+        (Some(parent), Some(filename)) => {
+            let mut partial_name = OsString::from(".");
+            partial_name.push(filename);
+            partial_name.push(format!("{}{}", PARTIAL_COPY_MARKER, std::process::id()));
+
+            Ok(parent.join(partial_name))
+        },
+        _ => Err(Error::new(ErrorKind::InvalidInput, format!("Cannot move anything to {}", to.display())))
+    }
+}
+
+/// Recognizes the temporary copies made by `move_by_copying`, returning the name the copy was meant to end up
+/// with and the id of the process making it
+pub fn parse_partial_copy_name(name: &OsStr) -> Option<(OsString, u32)> {
+    let name = name.as_bytes().strip_prefix(b".")?;
+    let marker = PARTIAL_COPY_MARKER.as_bytes();
+    let marker_start = name.windows(marker.len()).rposition(|window| window == marker)?;
+
+    let pid = std::str::from_utf8(&name[marker_start + marker.len()..]).ok()?.parse().ok()?;
+    Some((OsStr::from_bytes(&name[..marker_start]).to_owned(), pid))
+}
+
+/// Returned (wrapped in an `std::io::Error` of the kind of the underlying error) by `move_by_copying` when `to` holds
+/// a complete, verified copy of `from` but `from` could not be fully removed afterwards. Unlike other move errors,
+/// the move did happen. Use `IncompleteMoveError::from_io_error` to get it.
+#[derive(Debug)]
+pub struct IncompleteMoveError {
+    pub from: PathBuf,
+    pub to: PathBuf,
+
+    /// Why `from` could not be removed
+    pub error: Error
+}
+
+impl IncompleteMoveError {
+    /// Gets the `IncompleteMoveError` an I/O error was created from, if any
+    pub fn from_io_error(error: &Error) -> Option<&IncompleteMoveError> {
+        error.get_ref().and_then(|inner| inner.downcast_ref::<IncompleteMoveError>())
+    }
+}
+
+impl Display for IncompleteMoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "copied {} to {}, but the original could not be fully removed: {}", self.from.display(), self.to.display(), self.error)
+    }
+}
+
+impl StdError for IncompleteMoveError {}
+
+/// Removes a file, symlink or whole directory tree
+pub fn remove_path(path: &Path) -> Result<()> {
+    if symlink_metadata(path)?.is_dir() {
+        remove_dir_all(path)
+    } else {
+        remove_file(path)
+    }
+}
+
+/// Recursively copies `from` to `to` (which must not exist yet), preserving symlinks, permissions and modification times
+fn copy_path(from: &Path, to: &Path) -> Result<()> {
+    let metadata = symlink_metadata(from)?;
+    let file_type = metadata.file_type();
+    let times = FileTimes::new()
+        .set_accessed(metadata.accessed()?)
+        .set_modified(metadata.modified()?);
+
+    if file_type.is_symlink() {
+        symlink(read_link(from)?, to)
+    } else if file_type.is_dir() {
+        create_dir(to)?;
+        for entry in read_dir(from)? {
+            let entry = entry?;
+            copy_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+
+        // times are restored after the children are copied, since creating them updates the directory's mtime
+        File::open(to)?.set_times(times)?;
+        set_permissions(to, metadata.permissions())
+    } else if file_type.is_file() {
+        let mut source = File::open(from)?;
+        let mut destination = OpenOptions::new().write(true).create_new(true).open(to)?;
+        copy(&mut source, &mut destination)?;
+        destination.set_times(times)?;
+        set_permissions(to, metadata.permissions())
+    } else {
+        Err(Error::new(ErrorKind::Unsupported, format!("Cannot copy special file {}", from.display())))
+    }
+}
+
+/// Checks that `to` is a faithful copy of `from`: same file types, sizes, symlink targets and directory entries
+fn verify_copy(from: &Path, to: &Path) -> Result<()> {
+    let (from_metadata, to_metadata) = (symlink_metadata(from)?, symlink_metadata(to)?);
+    let mismatch = || Error::other(format!("Copy of {} does not match the original", from.display()));
+
+    if from_metadata.file_type() != to_metadata.file_type() {
+        return Err(mismatch());
+    }
+
+    if from_metadata.is_symlink() {
+        if read_link(from)? != read_link(to)? {
+            return Err(mismatch());
+        }
+    } else if from_metadata.is_dir() {
+        let mut entry_count = 0;
+        for entry in read_dir(from)? {
+            let entry = entry?;
+            verify_copy(&entry.path(), &to.join(entry.file_name()))?;
+            entry_count += 1;
+        }
+
+        if read_dir(to)?.count() != entry_count {
+            return Err(mismatch());
+        }
+    } else if from_metadata.len() != to_metadata.len() {
+        return Err(mismatch());
+    }
+
+    Ok(())
+}
+
+/// Moves `from` to `to` by copying it, verifying the copy and only then removing `from`. The copy is written next to
+/// `to` under a temporary name and renamed into place, so a failed copy never leaves a partial `to` behind. When `from`
+/// cannot be fully removed once `to` is in place, an `IncompleteMoveError` is returned.
+pub fn move_by_copying<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()> {
+    move_by_copying_with(from, to, remove_path)
+}
+
+/// Like `move_by_copying`, but removes `from` with `remove` once the copy is in place
+pub fn move_by_copying_with<P: AsRef<Path>, Q: AsRef<Path>, R: FnOnce(&Path) -> Result<()>>(from: P, to: Q, remove: R) -> Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let partial_copy = partial_copy_path(to)?;
+
+    // something already has the temporary name (e.g. a file trashed under it), it must not be overwritten or cleaned up
+    if symlink_metadata(&partial_copy).is_ok() {
+        return Err(Error::new(ErrorKind::AlreadyExists, format!("{} already exists", partial_copy.display())));
+    }
+
+    let copy_result = copy_path(from, &partial_copy)
+        .and_then(|_| verify_copy(from, &partial_copy))
+        .and_then(|_| rename(&partial_copy, to));
+
+    match copy_result {
+        Ok(_) => remove(from).map_err(|error| Error::new(error.kind(), IncompleteMoveError {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+            error
+        })),
+        Err(error) => {
+            if symlink_metadata(&partial_copy).is_ok() {
+                let _ = remove_path(&partial_copy);
+            }
+            Err(error)
+        }
+    }
+}
+
+/// Moves a file, directory or symlink, like `rename`, but falls back to copying and deleting
+/// when `from` and `to` are on different file systems
+pub fn move_path<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()> {
+    match rename(&from, &to) {
+        Ok(_) => Ok(()),
+        Err(error) if error.kind() == ErrorKind::CrossesDevices => move_by_copying(from, to),
+        Err(error) => Err(error)
+    }
+}
@@ -1,6 +1,14 @@
 pub mod common;
 pub mod actions;
 pub mod constants;
+pub mod directory_sizes;
+pub mod file_move;
+pub mod mount_table;
+pub mod protected_paths;
 pub mod string_encode;
+pub mod trash_filter;
 pub mod trash_info;
-pub mod tree;
\ No newline at end of file
+pub mod trash_repair;
+pub mod trash_selector;
+pub mod tree;
+pub mod tui;
\ No newline at end of file
@@ -1,30 +1,135 @@
-use std::error::Error;
+use std::{error::Error, ffi::OsString, fmt::Display, path::{Path, PathBuf}};
 
 use oscar::actions::{
-    trash_list::trash_list, 
-    trash_put::trash_put, 
-    trash_remove::trash_remove, 
-    trash_restore::trash_restore,
-    trash_empty::trash_empty
+    trash_list::{human_readable_size, relative_date, trash_list_with_options, ListColumn, ListFormat, ListOptions, ListSort}, 
+    trash_put::{check_rm_compatible, trash_put_many, PutOptions}, 
+    trash_remove::{trash_remove, trash_remove_many}, 
+    trash_restore::{restore_candidates, restore_destination, trash_restore_to, ConflictStrategy, MissingParentError, RestoreOptions, RestoreOutcome, RestoreReport},
+    trash_empty::{trash_empty, trash_empty_matching}
 };
+use chrono::{Local, NaiveDateTime};
 use clap::{Parser, Subcommand};
-use oscar::common::get_home_trash_contents;
-use inquire::{Confirm, InquireError, Select};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use glob::Pattern;
+use oscar::common::{exists_without_following, get_all_trash_contents};
+use oscar::directory_sizes::cached_trash_entry_size;
+use oscar::file_move::IncompleteMoveError;
+use oscar::protected_paths::ProtectedPathError;
+use oscar::trash_filter::{fuzzy_match_original_path, original_path_matcher, parse_date, resolve_filter_dir, TrashFilter};
+use oscar::trash_info::{TrashEntryType, TrashInfo};
+use oscar::trash_repair::repair_all_trash_dirs;
+use oscar::trash_selector::{lookup_trash_entries, AmbiguousSelectorError};
+use oscar::tui::run_trash_browser;
+use inquire::{Confirm, InquireError, MultiSelect, Select};
+use regex::Regex;
+
+fn parse_date_arg(s: &str) -> Result<NaiveDateTime, String> {
+    parse_date(s, Local::now().naive_local())
+}
+
+/// Options selecting trash entries, shared by the commands working on them
+#[derive(clap::Args, Debug)]
+struct FilterArgs {
+    /// Only entries deleted since a date (2024-05-01, "2024-05-01 13:30") or for a duration (30m, 12h, 3d, 2w)
+    #[arg(long, value_parser = parse_date_arg)]
+    since: Option<NaiveDateTime>,
+
+    /// Only entries deleted before a date or longer ago than a duration
+    #[arg(long, value_parser = parse_date_arg)]
+    before: Option<NaiveDateTime>,
+
+    /// Only entries whose original file name matches a glob, e.g. '*.log'
+    #[arg(long)]
+    name: Option<Pattern>,
+
+    /// Only entries whose original path matches a regular expression
+    #[arg(long)]
+    regex: Option<Regex>,
+
+    /// Only entries deleted from a directory
+    #[arg(long = "in", value_name = "DIR")]
+    in_dir: Option<PathBuf>,
+
+    /// With --in, also include entries deleted from its subdirectories
+    #[arg(long, requires = "in_dir", default_value_t=false)]
+    subdirs: bool,
+
+    /// Only entries of a type: file, dir or symlink
+    #[arg(long = "type", value_name = "TYPE")]
+    entry_type: Option<TrashEntryType>
+}
+
+impl From<FilterArgs> for TrashFilter {
+    fn from(args: FilterArgs) -> Self {
+        TrashFilter {
+            since: args.since,
+            before: args.before,
+            name: args.name,
+            regex: args.regex,
+            in_dir: args.in_dir.as_deref().map(resolve_filter_dir),
+            in_subdirs: args.subdirs,
+            entry_type: args.entry_type
+        }
+    }
+}
 
 #[derive(Subcommand, Debug)]
 enum OscarCommand {
-    /// place a file or directories in the system trash
+    /// place files or directories in the system trash
     #[clap(alias = "p")]
     Put {
-        /// path to the file or directory to be placed in the trash
-        path: String
+        /// paths to the files or directories to be placed in the trash. Use `--` to pass paths starting with `-`
+        #[arg(required_unless_present = "force")]
+        paths: Vec<PathBuf>,
+
+        /// Behave like rm(1) so oscar can stand in for it: no confirmation unless -i or -I is given,
+        /// directories require -r (or -d when empty) and missing files are errors unless -f is given
+        #[arg(long, default_value_t=false)]
+        rm: bool,
+
+        /// Ignore nonexistent files and never prompt
+        #[arg(short, long, default_value_t=false, overrides_with_all = ["interactive", "interactive_once"])]
+        force: bool,
+
+        /// Prompt before placing every item in the trash
+        #[arg(short = 'i', default_value_t=false, overrides_with_all = ["force", "interactive_once"])]
+        interactive: bool,
+
+        /// Prompt once before placing more than three items in the trash, or when placing directories recursively
+        #[arg(short = 'I', default_value_t=false, overrides_with_all = ["force", "interactive"])]
+        interactive_once: bool,
+
+        /// Place directories and their contents in the trash (only required with --rm)
+        #[arg(short, short_alias = 'R', long, default_value_t=false)]
+        recursive: bool,
+
+        /// Place empty directories in the trash (only required with --rm)
+        #[arg(short, long, default_value_t=false)]
+        dir: bool,
+
+        /// Allow placing protected paths in the trash: the file system root, your home directory,
+        /// trash directories, mount points and the paths listed in OSCAR_PROTECTED_PATHS
+        #[arg(long, default_value_t=false)]
+        allow_protected: bool
     },
 
     /// empty the system trash
     #[clap(alias = "e")]
     Empty {
         #[arg(short, long, default_value_t=false)]
-        yes: bool
+        yes: bool,
+
+        /// Only permanently delete the entries matching these options
+        #[command(flatten)]
+        filter: FilterArgs
+    },
+
+    /// browse the trash in a full screen terminal interface
+    #[clap(alias = "tui")]
+    Browse {
+        /// Only show the entries matching these options
+        #[command(flatten)]
+        filter: FilterArgs
     },
 
     /// list all files or directories in the trash
@@ -32,28 +137,256 @@ enum OscarCommand {
     List {
         /// List trash contents recursively
         #[arg(short, long, default_value_t=false)]
-        recursive: bool
+        recursive: bool,
+
+        /// Show the size of every entry and the number of items in directories. Directories may take a while to measure
+        #[arg(short, long, default_value_t=false)]
+        sizes: bool,
+
+        /// Show sizes in bytes instead of human readable sizes (implies --sizes)
+        #[arg(long, default_value_t=false)]
+        bytes: bool,
+
+        /// Show the full original path of every entry instead of its name and original location
+        #[arg(short, long, default_value_t=false)]
+        full_path: bool,
+
+        /// Comma separated columns to show, in order: name, location, path, date, type, size, items, trash
+        #[arg(short, long, value_delimiter = ',')]
+        columns: Vec<ListColumn>,
+
+        /// Output format: table, json, ndjson, csv or tsv. The recursive listing supports table and json
+        #[arg(long, default_value = "table")]
+        format: ListFormat,
+
+        /// Sort by name, date (most recent first), size (largest first) or path
+        #[arg(long, default_value = "date")]
+        sort: ListSort,
+
+        /// Reverse the sort order
+        #[arg(long, default_value_t=false)]
+        reverse: bool,
+
+        #[command(flatten)]
+        filter: FilterArgs
     },
 
     /// restore a file/directory in the trash to its original location
     #[clap(alias = "rs")]
     Restore {
-        /// the path of the file, relative to the system trash, to restore its original location
-        //path: Option<String>,
+        /// the entry to restore: its name in the trash, its original path, or a glob like '*.log'.
+        /// Without it, the entry is picked from a list
+        selector: Option<OsString>,
 
-        /// Overwrite the file currently on disk if there is a conflict
-        #[arg(long, default_value_t=false)]
-        overwrite: bool
+        /// Restore every entry matching the selector instead of failing when it matches several
+        #[arg(long, requires = "selector", default_value_t=false)]
+        all_matching: bool,
+
+        /// List entries deleted from anywhere, not just from the current directory and its subdirectories
+        #[arg(short, long, default_value_t=false)]
+        all: bool,
+
+        /// Pick several entries to restore at once
+        #[arg(short, long, conflicts_with = "selector", default_value_t=false)]
+        multiple: bool,
+
+        /// Overwrite the file currently on disk if there is a conflict, same as --on-conflict=overwrite
+        #[arg(long, default_value_t=false, conflicts_with = "on_conflict")]
+        overwrite: bool,
+
+        /// What to do when the destination already exists: fail, overwrite (places the existing file in the trash first),
+        /// rename (restores as `name (restored).ext`), skip, or merge (combines directories)
+        #[arg(long, default_value = "fail", value_name = "STRATEGY")]
+        on_conflict: ConflictStrategy,
+
+        /// What to do with conflicting files when merging directories: fail, overwrite, rename or skip
+        #[arg(long, default_value = "rename", value_name = "STRATEGY")]
+        on_file_conflict: ConflictStrategy,
+
+        /// Restore to this path instead of the original location, or into it when it is an existing directory
+        #[arg(long, value_name = "PATH")]
+        to: Option<PathBuf>,
+
+        /// Recreate the directories leading to the destination without asking when they no longer exist
+        #[arg(short, long, default_value_t=false)]
+        parents: bool,
+
+        #[command(flatten)]
+        filter: FilterArgs
     },
 
     /// remove individual files from the trashcan. 
     #[clap(alias = "rm")]
     Remove {
         #[arg(short, long, default_value_t=false)]
-        yes: bool
+        yes: bool,
+
+        /// Pick several entries to remove at once
+        #[arg(short, long, default_value_t=false)]
+        multiple: bool,
+
+        #[command(flatten)]
+        filter: FilterArgs
     },
 }
 
+/// When `oscar put` asks for confirmation
+#[derive(PartialEq)]
+enum PutPromptMode {
+    /// once for all items, oscar's default
+    Batch,
+    /// once before every item (-i)
+    Each,
+    /// once for more than three items or a recursive put (-I)
+    Once,
+    /// never (-f, and the default with --rm)
+    Never
+}
+
+/// Asks a yes/no question, a cancelled prompt counts as "no"
+fn confirm(message: &str) -> Result<bool, InquireError> {
+    match Confirm::new(message).with_default(false).prompt() {
+        Ok(answer) => Ok(answer),
+        Err(InquireError::OperationCanceled) => Ok(false),
+        Err(error) => Err(error)
+    }
+}
+
+/// Restores an entry, offering to recreate the directories leading to its destination when they no longer exist.
+/// Returns `None` when the user declined.
+fn restore_entry(trash_entry: &TrashInfo, destination: &Path, options: &RestoreOptions) -> Result<Option<RestoreReport>, Box<dyn Error>> {
+    let report = match trash_restore_to(trash_entry, destination, options) {
+        Ok(report) => report,
+        Err(error) => match MissingParentError::from_io_error(&error) {
+            Some(missing_parent_error) => {
+                let message = format!("{missing_parent_error}, recreate it?");
+                match confirm(&message) {
+                    Ok(true) => trash_restore_to(trash_entry, destination, &RestoreOptions { create_parents: true, ..options.clone() })?,
+                    Ok(false) => return Ok(None),
+                    Err(error) => return Err(Box::new(error))
+                }
+            },
+            None => return Err(Box::new(error))
+        }
+    };
+
+    for created_dir in &report.created_dirs {
+        println!("Recreated directory {}", created_dir.display());
+    }
+
+    match report.outcome {
+        RestoreOutcome::Renamed => println!("{} already exists, restored as {}", destination.display(), report.destination.display()),
+        RestoreOutcome::Overwritten => println!("Placed the existing {} in the trash", destination.display()),
+        RestoreOutcome::PartiallyMerged => println!("Merged into {}, conflicting items were left in the trash", destination.display()),
+        RestoreOutcome::Skipped => println!("Skipped {}, {} already exists", trash_entry.path.to_string_lossy(), destination.display()),
+        RestoreOutcome::Restored | RestoreOutcome::Merged => ()
+    }
+
+    Ok(Some(report))
+}
+
+/// A trash entry as offered by the interactive pickers, along with the row describing it
+struct PickerRow {
+    trash_info: TrashInfo,
+    label: String
+}
+
+impl Display for PickerRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// Describes every entry by name, original directory, deletion date, size and type in aligned columns, most recently
+/// deleted first. Directories are not walked to keep the picker quick, so their size is only shown when it is cached.
+fn picker_rows(mut trash_contents: Vec<TrashInfo>) -> Vec<PickerRow> {
+    trash_contents.sort_by_key(|trash_info| std::cmp::Reverse(trash_info.deletion_date));
+
+    let now = Local::now().naive_local();
+    let cells: Vec<[String; 5]> = trash_contents.iter().map(|trash_info| [
+        trash_info.path.to_string_lossy().into_owned(),
+        trash_info.full_path.parent().map(|parent| parent.display().to_string()).unwrap_or_default(),
+        relative_date(trash_info.deletion_date, now),
+        cached_trash_entry_size(trash_info).ok().flatten().map(human_readable_size).unwrap_or_default(),
+        trash_info.entry_type().map(|entry_type| entry_type.to_string()).unwrap_or_default()
+    ]).collect();
+
+    let mut widths = [0; 5];
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    trash_contents.into_iter().zip(cells).map(|(trash_info, [name, dir, date, size, entry_type])| PickerRow {
+        trash_info,
+        label: format!("{name:<0$}  {dir:<1$}  {date:<2$}  {size:>3$}  {entry_type}", widths[0], widths[1], widths[2], widths[3])
+    }).collect()
+}
+
+/// Fuzzy matches the filter against an entry's full original path. Rows scoring the same keep their order, so the
+/// most recently deleted stays on top.
+fn picker_row_score(matcher: &SkimMatcherV2, input: &str, row: &PickerRow, index: usize) -> Option<i64> {
+    let score = if input.is_empty() {
+        0
+    } else {
+        fuzzy_match_original_path(matcher, &row.trash_info, input)?
+    };
+
+    Some(score.saturating_mul(1 << 20).saturating_sub(index as i64))
+}
+
+/// Lets the user pick one entry, or several when `multiple` is set (→ selects all, ← selects none)
+fn pick_trash_entries(message: &str, trash_contents: Vec<TrashInfo>, multiple: bool) -> Result<Vec<TrashInfo>, InquireError> {
+    let rows = picker_rows(trash_contents);
+    let matcher = original_path_matcher();
+    let scorer = |input: &str, row: &PickerRow, _label: &str, index: usize| picker_row_score(&matcher, input, row, index);
+
+    let selected_rows = if multiple {
+        MultiSelect::new(message, rows).with_scorer(&scorer).prompt()?
+    } else {
+        vec![Select::new(message, rows).with_scorer(&scorer).prompt()?]
+    };
+
+    Ok(selected_rows.into_iter().map(|row| row.trash_info).collect())
+}
+
+/// Restores several entries, carrying on past failures, which are reported one by one. Exits with an error status
+/// when any of them failed.
+fn restore_batch(trash_entries: &[TrashInfo], to: Option<&Path>, options: &RestoreOptions, report_successes: bool) -> Result<(), Box<dyn Error>> {
+    if let Some(to) = to {
+        if trash_entries.len() > 1 && !to.is_dir() {
+            eprintln!("{} is not a directory, several entries cannot be restored to it", to.display());
+            std::process::exit(1);
+        }
+    }
+
+    let mut failure_count = 0;
+    for trash_entry in trash_entries {
+        let destination = match to {
+            Some(to) => restore_destination(trash_entry, to),
+            None => trash_entry.full_path.clone()
+        };
+
+        match restore_entry(trash_entry, &destination, options) {
+            Ok(Some(report)) if report_successes && report.outcome != RestoreOutcome::Skipped => {
+                println!("Restored {}", report.destination.display());
+            },
+            Ok(_) => (),
+            Err(error) => {
+                eprintln!("Failed to restore {} to {}: {}", trash_entry.full_path.display(), destination.display(), error);
+                failure_count += 1;
+            }
+        }
+    }
+
+    if failure_count > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
 /// Command Line tool to manage your system's Freedesktop.org trash
 /// written in Rust.
 #[derive(Parser, Debug)]
@@ -66,26 +399,152 @@ struct Args {
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
 
+    // clean up after puts that were interrupted by a crash, so they don't show up as entries that cannot be restored.
+    // Only commands that change the trash do so, listing it never deletes anything.
+    if matches!(args.cmd, OscarCommand::Put { .. } | OscarCommand::Restore { .. } | OscarCommand::Empty { .. }) {
+        let repaired_entries = repair_all_trash_dirs();
+        if !repaired_entries.is_empty() {
+            eprintln!("Cleaned up {} leftover(s) of interrupted trash operations", repaired_entries.len());
+        }
+    }
+
     match args.cmd {
-        OscarCommand::Put { path } => {
-            let should_place_in_trash_result = Confirm::new(format!("Are you sure you want to place {} in the trash?", path).as_str())
-                .with_default(false)
-                .prompt();
+        OscarCommand::Put { paths, rm, force, interactive, interactive_once, recursive, dir, allow_protected } => {
+            let prompt_mode = if force {
+                PutPromptMode::Never
+            } else if interactive {
+                PutPromptMode::Each
+            } else if interactive_once {
+                PutPromptMode::Once
+            } else if rm {
+                PutPromptMode::Never
+            } else {
+                PutPromptMode::Batch
+            };
+
+            // like rm -f, nonexistent files are silently ignored
+            let paths: Vec<PathBuf> = if force {
+                paths.into_iter().filter(|path| exists_without_following(path).unwrap_or(true)).collect()
+            } else {
+                paths
+            };
+
+            let should_place_in_trash_result = match prompt_mode {
+                PutPromptMode::Batch => {
+                    let message = match paths.as_slice() {
+                        [path] => format!("Are you sure you want to place {} in the trash?", path.display()),
+                        _ => {
+                            for path in &paths {
+                                println!("{}", path.display());
+                            }
+                            format!("Are you sure you want to place these {} items in the trash?", paths.len())
+                        }
+                    };
+                    confirm(&message)
+                },
+                PutPromptMode::Once if paths.len() > 3 || recursive => {
+                    confirm(&format!("Place {} items in the trash{}?", paths.len(), if recursive { " recursively" } else { "" }))
+                },
+                _ => Ok(true)
+            };
 
             match should_place_in_trash_result {
-                Ok(true) => match trash_put(&path) {
-                    Ok(_) => Ok(()),
-                    Err(error) => Err(Box::new(error))
+                Ok(true) => {
+                    let mut failure_count = 0;
+                    let mut paths_to_trash = vec![];
+
+                    for path in paths {
+                        if rm {
+                            if let Err(error) = check_rm_compatible(&path, recursive, dir) {
+                                eprintln!("Failed to place {} in the trash: {}", path.display(), error);
+                                failure_count += 1;
+                                continue;
+                            }
+                        }
+
+                        if prompt_mode == PutPromptMode::Each {
+                            match confirm(&format!("Place {} in the trash?", path.display())) {
+                                Ok(true) => (),
+                                Ok(false) => continue,
+                                Err(InquireError::OperationInterrupted) => return Ok(()),
+                                Err(error) => return Err(Box::new(error))
+                            }
+                        }
+
+                        paths_to_trash.push(path);
+                    }
+
+                    let put_options = PutOptions { allow_protected, ..PutOptions::default() };
+                    let results = trash_put_many(&paths_to_trash, &put_options);
+                    let print_successes = !rm && results.len() > 1;
+
+                    for (path, result) in &results {
+                        match result {
+                            Ok(_) => if print_successes {
+                                println!("Placed {} in the trash", path.display());
+                            },
+                            Err(error) => {
+                                if ProtectedPathError::from_io_error(error).is_some() {
+                                    eprintln!("{} (use --allow-protected to override)", error);
+                                } else if let Some(incomplete_move_error) = IncompleteMoveError::from_io_error(error) {
+                                    eprintln!("Placed {} in the trash, but it could not be fully removed: {}", path.display(), incomplete_move_error.error);
+                                } else {
+                                    eprintln!("Failed to place {} in the trash: {}", path.display(), error);
+                                }
+                                failure_count += 1;
+                            }
+                        }
+                    }
+
+                    // like rm, any failure makes the whole invocation fail
+                    if failure_count > 0 {
+                        std::process::exit(1);
+                    }
+
+                    Ok(())
                 },
                 Ok(false) => Ok(()),
                 Err(error) => match error {
-                    InquireError::OperationCanceled => Ok(()),
                     InquireError::OperationInterrupted => Ok(()),
                     _ => Err(Box::new(error))
                 }
             }
         },
-        OscarCommand::Empty { yes } => {
+        OscarCommand::Browse { filter } => match run_trash_browser(TrashFilter::from(filter)) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(Box::new(error))
+        },
+        OscarCommand::Empty { yes, filter } => {
+            let filter = TrashFilter::from(filter);
+
+            if !filter.is_empty() {
+                let matching_count = match get_all_trash_contents() {
+                    Ok(trash_contents) => filter.apply(trash_contents).len(),
+                    Err(error) => return Err(Box::new(error))
+                };
+
+                if matching_count == 0 {
+                    println!("No items in the trash match");
+                    return Ok(());
+                }
+
+                let should_empty_trash_result = if yes {
+                    Ok(true)
+                } else {
+                    confirm(&format!("Are you sure you want to permanently delete {matching_count} items from the trash? This action is irreversible."))
+                };
+
+                return match should_empty_trash_result {
+                    Ok(true) => match trash_empty_matching(&filter) {
+                        Ok(_) => Ok(()),
+                        Err(error) => Err(Box::new(error))
+                    },
+                    Ok(false) => Ok(()),
+                    Err(InquireError::OperationInterrupted) => Ok(()),
+                    Err(error) => Err(Box::new(error))
+                };
+            }
+
             if yes {
                 match trash_empty() {
                     Ok(_) => Ok(()),
@@ -112,29 +571,85 @@ fn main() -> Result<(), Box<dyn Error>> {
                 }
             }
         },
-        OscarCommand::List { recursive } => {
-            match trash_list(recursive) {
+        OscarCommand::List { recursive, sizes, bytes, full_path, columns, format, sort, reverse, filter } => {
+            let options = ListOptions { recursive, sizes: sizes || bytes, bytes, full_path, columns, format, sort, reverse, filter: filter.into() };
+            match trash_list_with_options(&options) {
                 Ok(_) => Ok(()),
                 Err(error) => Err(Box::new(error))
             }
         },
-        OscarCommand::Restore { overwrite } => {
-            match get_home_trash_contents() {
+        OscarCommand::Restore { selector: Some(selector), all_matching, overwrite, on_conflict, on_file_conflict, to, parents, filter, .. } => {
+            let restore_options = RestoreOptions {
+                on_conflict: if overwrite { ConflictStrategy::Overwrite } else { on_conflict },
+                on_file_conflict,
+                create_parents: parents
+            };
+
+            let trash_contents = match get_all_trash_contents() {
+                Ok(trash_contents) => TrashFilter::from(filter).apply(trash_contents),
+                Err(error) => return Err(Box::new(error))
+            };
+
+            match lookup_trash_entries(&trash_contents, &selector, all_matching) {
+                Ok(selected_items) => restore_batch(&selected_items, to.as_deref(), &restore_options, selected_items.len() > 1),
+                Err(error) => {
+                    if AmbiguousSelectorError::from_io_error(&error).is_some() {
+                        eprintln!("{error}\nUse --all-matching to restore all of them, or a more specific selector");
+                    } else {
+                        eprintln!("{error}");
+                    }
+
+                    std::process::exit(1);
+                }
+            }
+        },
+        OscarCommand::Restore { selector: None, all, multiple, overwrite, on_conflict, on_file_conflict, to, parents, filter, .. } => {
+            let restore_options = RestoreOptions {
+                on_conflict: if overwrite { ConflictStrategy::Overwrite } else { on_conflict },
+                on_file_conflict,
+                create_parents: parents
+            };
+
+            let current_dir = resolve_filter_dir(&std::env::current_dir()?);
+
+            match get_all_trash_contents() {
                 Ok(trash_contents) => {
-                    let user_response = Select::new("Select an item from the trash to restore", trash_contents).prompt();
+                    let trash_contents = TrashFilter::from(filter).apply(trash_contents);
+                    let trash_contents = restore_candidates(trash_contents, if all { None } else { Some(&current_dir) });
+                    if trash_contents.is_empty() {
+                        if all {
+                            println!("No items in the trash match");
+                        } else {
+                            println!("No items in the trash were deleted from {} (use --all to list everything)", current_dir.display());
+                        }
+                        return Ok(());
+                    }
 
-                    match user_response {
-                        Ok(selected_item) => {
-                            match trash_restore(&selected_item, overwrite) {
-                                Ok(_) => Ok(()),
-                                Err(error) => Err(Box::new(error))
-                            }
+                    let selected_items = match pick_trash_entries(if multiple { "Select items from the trash to restore" } else { "Select an item from the trash to restore" }, trash_contents, multiple) {
+                        Ok(selected_items) => selected_items,
+                        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => return Ok(()),
+                        Err(error) => return Err(Box::new(error))
+                    };
+
+                    match selected_items.as_slice() {
+                        [] => Ok(()),
+                        [selected_item] if !multiple => {
+                            let destination = match &to {
+                                Some(to) => restore_destination(selected_item, to),
+                                None => selected_item.full_path.clone()
+                            };
+
+                            restore_entry(selected_item, &destination, &restore_options).map(|_| ())
                         },
-                        Err(error) => {
-                            match error {
-                                InquireError::OperationCanceled => Ok(()),
-                                InquireError::OperationInterrupted => Ok(()),
-                                _ => Err(Box::new(error))
+                        _ => {
+                            for selected_item in &selected_items {
+                                println!("{}", selected_item.full_path.display());
+                            }
+
+                            match confirm(&format!("Restore these {} items?", selected_items.len())) {
+                                Ok(true) => restore_batch(&selected_items, to.as_deref(), &restore_options, true),
+                                Ok(false) | Err(InquireError::OperationInterrupted) => Ok(()),
+                                Err(error) => Err(Box::new(error))
                             }
                         }
                     }
@@ -142,44 +657,61 @@ fn main() -> Result<(), Box<dyn Error>> {
                 Err(error) => Err(Box::new(error))
             }
         },
-        OscarCommand::Remove { yes } => {
-            match get_home_trash_contents() {
+        OscarCommand::Remove { yes, multiple, filter } => {
+            match get_all_trash_contents() {
                 Ok(trash_contents) => {
-                    let user_response = Select::new("Select an item from the trash to remove", trash_contents).prompt();
-
-                    match user_response {
-                        Ok(selected_item) => {
-                            if yes {
-                                match trash_remove(&selected_item) {
-                                    Ok(_) => Ok(()),
-                                    Err(error) => Err(Box::new(error))
-                                }
-                            } else {
-                                let message = format!("Are you sure you want to delete {}? This action is irreversible.", selected_item.path.as_str());
-                                let should_rm_from_trash_result = Confirm::new(&message.as_str())
-                                    .with_default(false)
-                                    .prompt();
-
-                                match should_rm_from_trash_result {
-                                    Ok(true) => match trash_remove(&selected_item) {
-                                        Ok(_) => Ok(()),
-                                        Err(error) => Err(Box::new(error))
-                                    },
-                                    Ok(false) => Ok(()),
-                                    Err(error) => match error {
-                                        InquireError::OperationCanceled => Ok(()),
-                                        InquireError::OperationInterrupted => Ok(()),
-                                        _ => Err(Box::new(error))
+                    let trash_contents = TrashFilter::from(filter).apply(trash_contents);
+                    if trash_contents.is_empty() {
+                        println!("No items in the trash match");
+                        return Ok(());
+                    }
+
+                    let selected_items = match pick_trash_entries(if multiple { "Select items from the trash to remove" } else { "Select an item from the trash to remove" }, trash_contents, multiple) {
+                        Ok(selected_items) => selected_items,
+                        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => return Ok(()),
+                        Err(error) => return Err(Box::new(error))
+                    };
+
+                    let message = match selected_items.as_slice() {
+                        [] => return Ok(()),
+                        [selected_item] if !multiple => format!("Are you sure you want to delete {}? This action is irreversible.", selected_item.path.to_string_lossy()),
+                        _ => {
+                            for selected_item in &selected_items {
+                                println!("{}", selected_item.full_path.display());
+                            }
+                            format!("Are you sure you want to delete these {} items? This action is irreversible.", selected_items.len())
+                        }
+                    };
+
+                    let should_rm_from_trash_result = if yes { Ok(true) } else { confirm(&message) };
+
+                    match should_rm_from_trash_result {
+                        Ok(true) if multiple => {
+                            let mut failure_count = 0;
+                            for (selected_item, result) in trash_remove_many(&selected_items) {
+                                match result {
+                                    Ok(_) => println!("Removed {}", selected_item.full_path.display()),
+                                    Err(error) => {
+                                        eprintln!("Failed to remove {}: {}", selected_item.full_path.display(), error);
+                                        failure_count += 1;
                                     }
                                 }
                             }
-                        },
-                        Err(error) => {
-                            match error {
-                                InquireError::OperationCanceled => Ok(()),
-                                InquireError::OperationInterrupted => Ok(()),
-                                _ => Err(Box::new(error))
+
+                            if failure_count > 0 {
+                                std::process::exit(1);
                             }
+
+                            Ok(())
+                        },
+                        Ok(true) => match trash_remove(&selected_items[0]) {
+                            Ok(_) => Ok(()),
+                            Err(error) => Err(Box::new(error))
+                        },
+                        Ok(false) => Ok(()),
+                        Err(error) => match error {
+                            InquireError::OperationInterrupted => Ok(()),
+                            _ => Err(Box::new(error))
                         }
                     }
                 },
@@ -0,0 +1,81 @@
+use std::{
+    ffi::OsString,
+    fs::read_to_string,
+    io::Result,
+    os::unix::ffi::OsStringExt,
+    path::{Path, PathBuf},
+};
+
+use crate::constants::PROC_SELF_MOUNTINFO;
+
+/// A single mounted file system, as listed in a `mountinfo` file
+#[derive(Debug, Clone, PartialEq)]
+pub struct MountEntry {
+    pub mount_point: PathBuf,
+
+    /// The id of the mounted device, comparable with `st_dev`
+    pub device_id: u64
+}
+
+/// Undoes the octal escaping (`\040` for spaces, `\011` for tabs, etc) the kernel applies to paths in `mountinfo`
+fn unescape_mount_path(escaped: &str) -> PathBuf {
+    let bytes = escaped.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+
+        match (bytes[i], octal) {
+            (b'\\', Some(byte)) => {
+                unescaped.push(byte);
+                i += 4;
+            },
+            (byte, _) => {
+                unescaped.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    PathBuf::from(OsString::from_vec(unescaped))
+}
+
+fn parse_mount_entry(line: &str) -> Option<MountEntry> {
+    // mount-id parent-id major:minor root mount-point options ...
+    let mut fields = line.split_whitespace();
+    let device = fields.nth(2)?;
+    let mount_point = fields.nth(1)?;
+
+    let (major, minor) = device.split_once(':')?;
+
+    Some(MountEntry {
+        mount_point: unescape_mount_path(mount_point),
+        device_id: libc::makedev(major.parse().ok()?, minor.parse().ok()?)
+    })
+}
+
+/// Parses the contents of a `mountinfo` file (see proc(5)). Malformed lines are skipped.
+pub fn parse_mount_table(contents: &str) -> Vec<MountEntry> {
+    contents.lines().filter_map(parse_mount_entry).collect()
+}
+
+/// Reads the mount table from a `mountinfo` file, normally `/proc/self/mountinfo`
+pub fn read_mount_table<P: AsRef<Path>>(path: P) -> Result<Vec<MountEntry>> {
+    Ok(parse_mount_table(&read_to_string(path)?))
+}
+
+/// Reads the mount table of the current process
+pub fn current_mount_table() -> Result<Vec<MountEntry>> {
+    read_mount_table(PROC_SELF_MOUNTINFO)
+}
+
+/// Finds the mount `path` lives on: the deepest mount point containing `path` whose device is `device_id`
+pub fn find_mount<'m>(mounts: &'m [MountEntry], path: &Path, device_id: u64) -> Option<&'m MountEntry> {
+    mounts.iter()
+        .filter(|mount| mount.device_id == device_id && path.starts_with(&mount.mount_point))
+        .max_by_key(|mount| mount.mount_point.components().count())
+}
+
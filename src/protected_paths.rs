@@ -0,0 +1,136 @@
+use std::{
+    env::{split_paths, var, var_os},
+    error::Error as StdError,
+    fmt::Display,
+    fs::{canonicalize, symlink_metadata},
+    io::{Error, ErrorKind, Result},
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    common::{all_trash_dirs, existing_topdir_trash_dirs},
+    constants::OSCAR_PROTECTED_PATHS_VAR,
+    mount_table::current_mount_table,
+};
+
+/// Why a path may not be placed in the trash
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProtectionReason {
+    FileSystemRoot,
+    /// The path is the home directory or one of its ancestors
+    HomeDirectory,
+    /// The path is a trash directory or lies inside one
+    InsideTrash(PathBuf),
+    /// The path contains a trash directory, which would end up being moved into itself
+    ContainsTrash(PathBuf),
+    MountPoint,
+    /// The path is, or contains, a path listed in `OSCAR_PROTECTED_PATHS` or passed in by the caller
+    Configured(PathBuf),
+}
+
+/// Returned (wrapped in an `std::io::Error` of kind `PermissionDenied`) when asked to trash a protected path.
+/// Use `ProtectedPathError::from_io_error` to tell it apart from I/O failures.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtectedPathError {
+    pub path: PathBuf,
+    pub reason: ProtectionReason,
+}
+
+impl ProtectedPathError {
+    /// Gets the `ProtectedPathError` an I/O error was created from, if any
+    pub fn from_io_error(error: &Error) -> Option<&ProtectedPathError> {
+        error.get_ref().and_then(|inner| inner.downcast_ref::<ProtectedPathError>())
+    }
+}
+
+impl Display for ProtectedPathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match &self.reason {
+            ProtectionReason::FileSystemRoot => String::from("it is the root of the file system"),
+            ProtectionReason::HomeDirectory => String::from("it is or contains your home directory"),
+            ProtectionReason::InsideTrash(trash_dir) => format!("it is inside the trash directory {}", trash_dir.display()),
+            ProtectionReason::ContainsTrash(trash_dir) => format!("it contains the trash directory {}", trash_dir.display()),
+            ProtectionReason::MountPoint => String::from("it is a mount point"),
+            ProtectionReason::Configured(protected_path) => format!("the path {} is protected", protected_path.display()),
+        };
+
+        write!(f, "refusing to place {} in the trash: {}", self.path.display(), reason)
+    }
+}
+
+impl StdError for ProtectedPathError {}
+
+/// Extra protected paths from the `OSCAR_PROTECTED_PATHS` environment variable, separated by `:` like `PATH`
+pub fn configured_protected_paths() -> Vec<PathBuf> {
+    match var_os(OSCAR_PROTECTED_PATHS_VAR) {
+        Some(protected_paths) => split_paths(&protected_paths)
+            .filter(|path| !path.as_os_str().is_empty())
+            .collect(),
+        None => vec![]
+    }
+}
+
+/// Whether trashing `path` would take `protected_path` along, i.e. `path` is `protected_path` or one of its ancestors.
+/// Symlinks in `protected_path` are resolved when possible, so e.g. a symlinked $HOME is still protected.
+fn would_trash(path: &Path, protected_path: &Path) -> bool {
+    protected_path.starts_with(path) || canonicalize(protected_path).is_ok_and(|protected_path| protected_path.starts_with(path))
+}
+
+fn is_mount_point(path: &Path) -> bool {
+    if let Ok(mounts) = current_mount_table() {
+        if mounts.iter().any(|mount| mount.mount_point == path) {
+            return true;
+        }
+    }
+
+    // mount tables are not always available, fall back to comparing devices with the parent
+    match (symlink_metadata(path), path.parent().map(symlink_metadata)) {
+        (Ok(metadata), Some(Ok(parent_metadata))) => metadata.is_dir() && metadata.dev() != parent_metadata.dev(),
+        _ => false
+    }
+}
+
+/// Checks that `path`, which must be absolute, may be placed in the trash. `additional_protected_paths`
+/// are refused on top of the root, the home directory, trash directories, mount points and `OSCAR_PROTECTED_PATHS`.
+pub fn check_not_protected(path: &Path, additional_protected_paths: &[PathBuf]) -> Result<()> {
+    let protected = |reason: ProtectionReason| Err(Error::new(
+        ErrorKind::PermissionDenied,
+        ProtectedPathError { path: path.to_path_buf(), reason }
+    ));
+
+    if path.parent().is_none() {
+        return protected(ProtectionReason::FileSystemRoot);
+    }
+
+    if var("HOME").is_ok_and(|home| would_trash(path, Path::new(&home))) {
+        return protected(ProtectionReason::HomeDirectory);
+    }
+
+    let mut trash_dirs = all_trash_dirs();
+    for ancestor in path.ancestors() {
+        trash_dirs.extend(existing_topdir_trash_dirs(ancestor));
+    }
+
+    for trash_dir in trash_dirs {
+        let trash_dir = canonicalize(&trash_dir).unwrap_or(trash_dir);
+        if path.starts_with(&trash_dir) {
+            return protected(ProtectionReason::InsideTrash(trash_dir));
+        }
+        if trash_dir.starts_with(path) {
+            return protected(ProtectionReason::ContainsTrash(trash_dir));
+        }
+    }
+
+    if is_mount_point(path) {
+        return protected(ProtectionReason::MountPoint);
+    }
+
+    for protected_path in configured_protected_paths().iter().chain(additional_protected_paths) {
+        if would_trash(path, protected_path) {
+            return protected(ProtectionReason::Configured(protected_path.clone()));
+        }
+    }
+
+    Ok(())
+}
@@ -1,15 +1,52 @@
-pub fn decode_filename(s: &str) -> String {
-    s.replace("%20", " ")
-        .replace("%2C", ",")
-        .replace("%7B", "{")
-        .replace("%7C", "|")
-        .replace("%7D", "}")
-}
-
-pub fn encode_filename(s: &str) -> String {
-    s.replace(" ", "%20")
-        .replace(",", "%2C")
-        .replace("{", "%7B")
-        .replace("|", "%7C")
-        .replace("}", "%7D")
-}
\ No newline at end of file
+/// Bytes that may appear unescaped in the `Path` key of a trashinfo file: RFC 3986 unreserved characters
+/// plus `/`, which the Freedesktop.org spec requires to be kept as-is
+fn is_unescaped(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'/')
+}
+
+fn hex_value(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|value| value as u8)
+}
+
+/// Percent-decodes the `Path` key of a trashinfo file back into the raw bytes of the path.
+/// Malformed escapes (a `%` not followed by two hex digits) are kept literally.
+pub fn decode_filename(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped_byte = match (bytes[i], bytes.get(i + 1), bytes.get(i + 2)) {
+            (b'%', Some(&high), Some(&low)) => hex_value(high).zip(hex_value(low)).map(|(high, low)| high << 4 | low),
+            _ => None
+        };
+
+        match escaped_byte {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            },
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    decoded
+}
+
+/// Percent-encodes the raw bytes of a path as described in RFC 3986, for use as the `Path` key of a trashinfo file
+pub fn encode_filename(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len());
+
+    for &byte in bytes {
+        if is_unescaped(byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    encoded
+}
@@ -0,0 +1,118 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use glob::Pattern;
+use regex::Regex;
+
+use crate::trash_info::{TrashEntryType, TrashInfo};
+
+/// Parses a date given on the command line: either an absolute date (`2024-05-01`, `2024-05-01 13:30`,
+/// `2024-05-01T13:30:00`) or a duration before `now` (`30m`, `12h`, `3d`, `2w`)
+pub fn parse_date(s: &str, now: NaiveDateTime) -> Result<NaiveDateTime, String> {
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(s, format) {
+            return Ok(date);
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default());
+    }
+
+    let invalid = || format!("invalid date `{s}`, expected a date like 2024-05-01 or a duration like 3d or 2w");
+    let (amount, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+
+    let duration = match unit {
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => None
+    };
+
+    duration
+        .and_then(|duration| now.checked_sub_signed(duration))
+        .ok_or_else(invalid)
+}
+
+/// Resolves a directory to compare original paths with. Original paths are stored with their parent directories
+/// resolved, so `dir` is canonicalized, or only made absolute when it no longer exists.
+pub fn resolve_filter_dir(dir: &Path) -> PathBuf {
+    dir.canonicalize().or_else(|_| std::path::absolute(dir)).unwrap_or_else(|_| dir.to_path_buf())
+}
+
+/// Selects trash entries. Every criterion that is set has to match, an empty filter matches everything.
+#[derive(Debug, Default, Clone)]
+pub struct TrashFilter {
+    /// Only entries deleted at or after this date
+    pub since: Option<NaiveDateTime>,
+
+    /// Only entries deleted before this date
+    pub before: Option<NaiveDateTime>,
+
+    /// A glob the original file name has to match
+    pub name: Option<Pattern>,
+
+    /// A regular expression the original path has to match
+    pub regex: Option<Regex>,
+
+    /// Only entries deleted from this directory
+    pub in_dir: Option<PathBuf>,
+
+    /// Also include entries deleted from the subdirectories of `in_dir`
+    pub in_subdirs: bool,
+
+    /// Only entries of this type
+    pub entry_type: Option<TrashEntryType>
+}
+
+impl TrashFilter {
+    pub fn is_empty(&self) -> bool {
+        self.since.is_none()
+            && self.before.is_none()
+            && self.name.is_none()
+            && self.regex.is_none()
+            && self.in_dir.is_none()
+            && self.entry_type.is_none()
+    }
+
+    fn matches_in_dir(&self, original_path: &Path) -> bool {
+        match (&self.in_dir, original_path.parent()) {
+            (None, _) => true,
+            (Some(in_dir), Some(parent)) if self.in_subdirs => parent.starts_with(in_dir),
+            (Some(in_dir), Some(parent)) => parent == in_dir,
+            (Some(_), None) => false
+        }
+    }
+
+    pub fn matches(&self, trash_info: &TrashInfo) -> bool {
+        let file_name = trash_info.full_path.file_name().unwrap_or(trash_info.path.as_os_str()).to_string_lossy();
+
+        self.since.is_none_or(|since| trash_info.deletion_date >= since)
+            && self.before.is_none_or(|before| trash_info.deletion_date < before)
+            && self.name.as_ref().is_none_or(|name| name.matches(&file_name))
+            && self.regex.as_ref().is_none_or(|regex| regex.is_match(&trash_info.full_path.to_string_lossy()))
+            && self.matches_in_dir(&trash_info.full_path)
+            && self.entry_type.is_none_or(|entry_type| trash_info.entry_type().is_ok_and(|actual| actual == entry_type))
+    }
+
+    /// Keeps the entries matching the filter
+    pub fn apply(&self, trash_contents: Vec<TrashInfo>) -> Vec<TrashInfo> {
+        trash_contents.into_iter().filter(|trash_info| self.matches(trash_info)).collect()
+    }
+}
+
+/// The matcher to pass to `fuzzy_match_original_path`. Building one is not free, so build it once per search rather
+/// than once per entry.
+pub fn original_path_matcher() -> SkimMatcherV2 {
+    SkimMatcherV2::default().ignore_case()
+}
+
+/// Fuzzy matches `pattern` against an entry's full original path, ignoring case when `matcher` comes from
+/// `original_path_matcher`. Higher scores are better matches, `None` means no match.
+pub fn fuzzy_match_original_path(matcher: &SkimMatcherV2, trash_info: &TrashInfo, pattern: &str) -> Option<i64> {
+    matcher.fuzzy_match(&trash_info.full_path.to_string_lossy(), pattern)
+}
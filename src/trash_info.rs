@@ -1,27 +1,113 @@
-use std::{fmt::Display, fs::read_to_string, io::{Error, ErrorKind, Result}, path::{Path, PathBuf}};
+use std::{
+    ffi::{OsStr, OsString},
+    fmt::Display,
+    fs::read_to_string,
+    io::{Error, Result},
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+    str::FromStr
+};
 use chrono::NaiveDateTime;
 use configparser::ini::Ini;
-use tabled::Tabled;
-use crate::{constants::*, string_encode::decode_filename};
+use crate::{
+    common::{trash_dir_top_dir, trash_files_dir, trash_info_dir, with_trashinfo_extension},
+    constants::*,
+    string_encode::decode_filename
+};
 
-fn from_option(s: &Option<String>) -> String {
-    s.clone().unwrap()
+/// The name of the trash entry a trashinfo file describes, i.e. its file name without the `.trashinfo` extension
+pub fn trash_entry_name(trash_info_path: &Path) -> Option<OsString> {
+    trash_info_path
+        .file_name()?
+        .as_bytes()
+        .strip_suffix(TRASH_INFO_FILE_EXTENSION.as_bytes())?
+        .strip_suffix(b".")
+        .map(|name| OsStr::from_bytes(name).to_owned())
 }
 
-#[derive(Tabled, Debug, PartialEq)]
-#[tabled(rename_all = "CamelCase")]
+/// What kind of file a trash entry is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrashEntryType {
+    File,
+    Directory,
+    Symlink
+}
+
+impl Display for TrashEntryType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrashEntryType::File => write!(f, "file"),
+            TrashEntryType::Directory => write!(f, "dir"),
+            TrashEntryType::Symlink => write!(f, "symlink")
+        }
+    }
+}
+
+impl FromStr for TrashEntryType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "file" => Ok(TrashEntryType::File),
+            "dir" => Ok(TrashEntryType::Directory),
+            "symlink" => Ok(TrashEntryType::Symlink),
+            _ => Err(format!("unknown type `{s}`, expected one of file, dir, symlink"))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct TrashInfo {
-    #[tabled(skip)]
-    pub full_path: String,
+    /// Where the entry was deleted from
+    pub full_path: PathBuf,
 
-    #[tabled(rename = "Path")]
-    pub path: String,
+    /// The name of the entry in the trash directory
+    pub path: OsString,
 
-    #[tabled(rename = "Deletion Date")]
-    pub deletion_date: NaiveDateTime
+    pub deletion_date: NaiveDateTime,
+
+    /// The trash directory this entry lives in
+    pub trash_dir: PathBuf
+}
+
+/// Trashinfo files in top directory trashes may store their path relative to the top directory,
+/// turns such paths back into absolute ones
+fn resolve_original_path(trash_dir: &Path, original_path: PathBuf) -> PathBuf {
+    match trash_dir_top_dir(trash_dir) {
+        Some(top_dir) if original_path.is_relative() => top_dir.join(original_path),
+        _ => original_path
+    }
 }
 
 impl TrashInfo {
+    /// Location of this entry's `.trashinfo` file
+    pub fn trash_info_path(&self) -> PathBuf {
+        with_trashinfo_extension(&trash_info_dir(&self.trash_dir).join(&self.path))
+    }
+
+    /// Location of the trashed file or directory itself
+    pub fn trash_file_path(&self) -> PathBuf {
+        trash_files_dir(&self.trash_dir).join(&self.path)
+    }
+
+    /// The top directory of the trash this entry lives in, `None` when it is in the home trash
+    pub fn top_dir(&self) -> Option<PathBuf> {
+        trash_dir_top_dir(&self.trash_dir)
+    }
+
+    /// Whether the trashed entry is a file, directory or symlink. Symlinks are not followed.
+    pub fn entry_type(&self) -> Result<TrashEntryType> {
+        let file_type = self.trash_file_path().symlink_metadata()?.file_type();
+
+        if file_type.is_symlink() {
+            Ok(TrashEntryType::Symlink)
+        } else if file_type.is_dir() {
+            Ok(TrashEntryType::Directory)
+        } else {
+            Ok(TrashEntryType::File)
+        }
+    }
+
     pub fn from_file(path: PathBuf) -> Result<TrashInfo> {
         let (trash_info_header, path_field, deletion_date_field) = (
             String::from(TRASH_INFO_SECTION_HEADER).to_lowercase(),
@@ -29,7 +115,18 @@ impl TrashInfo {
             String::from(TRASH_INFO_DELETION_DATE_KEY).to_lowercase()
         );
 
-        let file_contents = read_to_string(path)?;
+        let trash_entry_name = match trash_entry_name(&path) {
+            Some(name) => name,
+            None => return Err(Error::other(format!("{} is not a trashinfo file", path.display())))
+        };
+
+        // trashinfo files always live in $trash/info
+        let trash_dir = match path.parent().and_then(Path::parent) {
+            Some(trash_dir) => trash_dir.to_path_buf(),
+            None => return Err(Error::other(format!("{} is not inside a trash directory", path.display())))
+        };
+
+        let file_contents = read_to_string(&path)?;
 
         let mut ini = Ini::new();
         if let Ok(map) = ini.read(file_contents) {
@@ -44,40 +141,38 @@ impl TrashInfo {
                     None => None
                 };
 
-                if full_path.is_some() && deletion_date_str.is_some() {
+                if let (Some(full_path), Some(deletion_date_str)) = (full_path, deletion_date_str) {
                     let deletion_date_result = NaiveDateTime::parse_from_str(
-                        deletion_date_str.unwrap().as_str(),
+                        deletion_date_str.as_str(),
                         "%Y-%m-%dT%H:%M:%S"
                     );
 
                     match deletion_date_result {
                         Ok(date) => Ok(TrashInfo { 
-                            full_path: from_option(&full_path),
-                            path: decode_filename(
-                                Path::new(&from_option(&full_path))
-                                    .file_name()
-                                    .unwrap()
-                                    .to_str()
-                                    .unwrap()
-                            ), 
-                            deletion_date: date
+                            full_path: resolve_original_path(
+                                &trash_dir,
+                                PathBuf::from(OsString::from_vec(decode_filename(&full_path)))
+                            ),
+                            path: trash_entry_name, 
+                            deletion_date: date,
+                            trash_dir
                         }),
-                        Err(err) => Err(Error::new(ErrorKind::Other, err.to_string()))
+                        Err(err) => Err(Error::other(err.to_string()))
                     }
                 } else {
-                    Err(Error::new(ErrorKind::Other, "Trash info missing full path or date"))
+                    Err(Error::other("Trash info missing full path or date"))
                 }
             } else {
-                Err(Error::new(ErrorKind::Other, "Trash info file invalid format"))
+                Err(Error::other("Trash info file invalid format"))
             }
         } else {
-            Err(Error::new(ErrorKind::Other, "Failed to read trash info file"))
+            Err(Error::other("Failed to read trash info file"))
         }
     }
 }
 
 impl Display for TrashInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.path)
+        write!(f, "{}", self.path.to_string_lossy())
     }
 }
\ No newline at end of file
@@ -0,0 +1,116 @@
+use std::{
+    ffi::OsString,
+    fs::{read_dir, remove_file},
+    io::{ErrorKind, Result},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    common::{all_trash_dirs, exists_without_following, trash_files_dir, trash_info_dir, with_trashinfo_extension},
+    file_move::{parse_partial_copy_name, remove_path},
+    trash_info::trash_entry_name,
+};
+
+/// How old a trashinfo file without a trashed file must be before it is considered abandoned. Younger ones may belong
+/// to a put that is still in progress in another process.
+const ORPHANED_TRASH_INFO_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Leftovers of a put (or restore) that was interrupted by a crash or SIGKILL
+#[derive(Debug, Clone, PartialEq)]
+pub enum IncompleteTrashEntry {
+    /// A trashinfo file whose file never made it to (or already left) the trash
+    OrphanedTrashInfo(PathBuf),
+
+    /// A partial copy left behind by an interrupted move across file systems
+    PartialCopy(PathBuf),
+}
+
+fn is_process_alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+/// Names of the entries currently being copied into the trash by a live process
+fn entries_being_copied(trash_dir: &Path) -> Result<Vec<OsString>> {
+    let mut names = vec![];
+
+    for entry in read_dir(trash_files_dir(trash_dir))? {
+        if let Some((name, pid)) = parse_partial_copy_name(&entry?.file_name()) {
+            if is_process_alive(pid) {
+                names.push(name);
+            }
+        }
+    }
+
+    Ok(names)
+}
+
+/// Finds the leftovers of interrupted puts in `trash_dir`
+pub fn find_incomplete_trash_entries(trash_dir: &Path) -> Result<Vec<IncompleteTrashEntry>> {
+    let mut incomplete_entries = vec![];
+
+    for entry in read_dir(trash_files_dir(trash_dir))? {
+        let entry = entry?;
+
+        // a file that merely looks like a partial copy may have been trashed on purpose, it then has a trashinfo file
+        let has_trash_info = exists_without_following(&with_trashinfo_extension(&trash_info_dir(trash_dir).join(entry.file_name())))?;
+        if let Some((_, pid)) = parse_partial_copy_name(&entry.file_name()).filter(|_| !has_trash_info) {
+            if !is_process_alive(pid) {
+                incomplete_entries.push(IncompleteTrashEntry::PartialCopy(entry.path()));
+            }
+        }
+    }
+
+    let being_copied = entries_being_copied(trash_dir)?;
+    let now = SystemTime::now();
+
+    for entry in read_dir(trash_info_dir(trash_dir))? {
+        let trash_info_path = entry?.path();
+        let Some(name) = trash_entry_name(&trash_info_path) else {
+            continue;
+        };
+
+        if exists_without_following(&trash_files_dir(trash_dir).join(&name))? || being_copied.contains(&name) {
+            continue;
+        }
+
+        let is_abandoned = trash_info_path.symlink_metadata()?
+            .modified()
+            .is_ok_and(|modified| now.duration_since(modified).unwrap_or_default() >= ORPHANED_TRASH_INFO_GRACE_PERIOD);
+
+        if is_abandoned {
+            incomplete_entries.push(IncompleteTrashEntry::OrphanedTrashInfo(trash_info_path));
+        }
+    }
+
+    Ok(incomplete_entries)
+}
+
+/// Removes the leftovers of interrupted puts in `trash_dir`. Files that were never moved into the trash are still in
+/// their original location, so only the trashinfo files and partial copies need to go. Returns what was removed.
+pub fn repair_trash_dir(trash_dir: &Path) -> Result<Vec<IncompleteTrashEntry>> {
+    let incomplete_entries = find_incomplete_trash_entries(trash_dir)?;
+
+    for incomplete_entry in &incomplete_entries {
+        let result = match incomplete_entry {
+            IncompleteTrashEntry::OrphanedTrashInfo(trash_info_path) => remove_file(trash_info_path),
+            IncompleteTrashEntry::PartialCopy(partial_copy_path) => remove_path(partial_copy_path)
+        };
+
+        match result {
+            Err(error) if error.kind() != ErrorKind::NotFound => return Err(error),
+            _ => ()
+        }
+    }
+
+    Ok(incomplete_entries)
+}
+
+/// Repairs the home trash and every top directory trash. Trash directories that cannot be read or repaired are skipped.
+pub fn repair_all_trash_dirs() -> Vec<IncompleteTrashEntry> {
+    all_trash_dirs()
+        .iter()
+        .filter_map(|trash_dir| repair_trash_dir(trash_dir).ok())
+        .flatten()
+        .collect()
+}
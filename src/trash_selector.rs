@@ -0,0 +1,101 @@
+use std::{
+    error::Error as StdError,
+    ffi::OsStr,
+    fmt::Display,
+    io::{Error, ErrorKind, Result},
+    os::unix::ffi::OsStrExt,
+    path::{absolute, Path},
+};
+
+use glob::{MatchOptions, Pattern};
+
+use crate::{common::get_all_trash_contents, trash_info::TrashInfo};
+
+/// Returned (wrapped in an `std::io::Error` of kind `InvalidInput`) when a selector matches several trash entries
+/// but only one was asked for. Use `AmbiguousSelectorError::from_io_error` to get the candidates.
+#[derive(Debug, PartialEq)]
+pub struct AmbiguousSelectorError {
+    pub selector: String,
+    pub candidates: Vec<TrashInfo>,
+}
+
+impl AmbiguousSelectorError {
+    /// Gets the `AmbiguousSelectorError` an I/O error was created from, if any
+    pub fn from_io_error(error: &Error) -> Option<&AmbiguousSelectorError> {
+        error.get_ref().and_then(|inner| inner.downcast_ref::<AmbiguousSelectorError>())
+    }
+}
+
+impl Display for AmbiguousSelectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} matches {} entries in the trash:", self.selector, self.candidates.len())?;
+        for candidate in &self.candidates {
+            write!(f, "\n  {} (deleted {}, {} in {})", candidate.full_path.display(), candidate.deletion_date, candidate.path.to_string_lossy(), candidate.trash_dir.display())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl StdError for AmbiguousSelectorError {}
+
+fn is_glob(selector: &str) -> bool {
+    selector.contains(['*', '?', '['])
+}
+
+/// Finds the trash entries a selector refers to. In order of precedence, a selector is
+/// - an original path, when it contains a `/`. Relative paths are resolved against the current directory.
+/// - the name of an entry in the trash (e.g. `notes.2.txt`) or the original file name of entries (e.g. `notes.txt`)
+/// - a glob matched against entry names, original file names and original paths, e.g. `*.log` or `/tmp/*.log`
+pub fn select_trash_entries<'a>(trash_contents: &'a [TrashInfo], selector: &OsStr) -> Vec<&'a TrashInfo> {
+    let select = |predicate: &dyn Fn(&TrashInfo) -> bool| trash_contents.iter().filter(|t| predicate(t)).collect::<Vec<_>>();
+
+    if selector.as_bytes().contains(&b'/') {
+        if let Ok(original_path) = absolute(Path::new(selector)) {
+            let matches = select(&|trash_info| trash_info.full_path == original_path);
+            if !matches.is_empty() {
+                return matches;
+            }
+        }
+    } else {
+        let matches = select(&|trash_info| trash_info.path == selector || trash_info.full_path.file_name() == Some(selector));
+        if !matches.is_empty() {
+            return matches;
+        }
+    }
+
+    let selector = selector.to_string_lossy();
+    match Pattern::new(&selector) {
+        Ok(pattern) if is_glob(&selector) => {
+            let path_options = MatchOptions { require_literal_separator: true, ..MatchOptions::default() };
+
+            select(&|trash_info| {
+                pattern.matches(&trash_info.path.to_string_lossy())
+                    || trash_info.full_path.file_name().is_some_and(|name| pattern.matches(&name.to_string_lossy()))
+                    || pattern.matches_with(&trash_info.full_path.to_string_lossy(), path_options)
+            })
+        },
+        _ => vec![]
+    }
+}
+
+/// Resolves a selector (see `select_trash_entries`) to trash entries. Fails with `NotFound` when nothing matches,
+/// and with an `AmbiguousSelectorError` when several entries match and `all_matching` is not set.
+pub fn lookup_trash_entries(trash_contents: &[TrashInfo], selector: &OsStr, all_matching: bool) -> Result<Vec<TrashInfo>> {
+    let matches = select_trash_entries(trash_contents, selector);
+
+    match matches.len() {
+        0 => Err(Error::new(ErrorKind::NotFound, format!("nothing in the trash matches {}", selector.to_string_lossy()))),
+        1 => Ok(matches.into_iter().cloned().collect()),
+        _ if all_matching => Ok(matches.into_iter().cloned().collect()),
+        _ => Err(Error::new(ErrorKind::InvalidInput, AmbiguousSelectorError {
+            selector: selector.to_string_lossy().into_owned(),
+            candidates: matches.into_iter().cloned().collect()
+        }))
+    }
+}
+
+/// Resolves a selector against the contents of every trash directory
+pub fn find_trash_entries(selector: &OsStr, all_matching: bool) -> Result<Vec<TrashInfo>> {
+    lookup_trash_entries(&get_all_trash_contents()?, selector, all_matching)
+}
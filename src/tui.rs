@@ -0,0 +1,469 @@
+use std::{
+    collections::HashSet,
+    fs::{read_dir, read_link, File},
+    io::{Read, Result},
+    path::Path
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Cell, Clear, Paragraph, Row, Table, TableState, Wrap},
+    DefaultTerminal, Frame
+};
+
+use crate::{
+    actions::{
+        trash_list::{human_readable_size, item_count},
+        trash_remove::trash_remove_many,
+        trash_restore::{trash_restore_to, RestoreOptions}
+    },
+    common::get_all_trash_contents,
+    directory_sizes::cached_trash_entry_size,
+    trash_filter::{fuzzy_match_original_path, original_path_matcher, TrashFilter},
+    trash_info::{TrashEntryType, TrashInfo},
+    tree::Tree
+};
+
+/// How much of a text file the preview pane reads
+const PREVIEW_MAX_BYTES: u64 = 16 * 1024;
+
+/// How deep the preview pane walks into directories
+const PREVIEW_MAX_DEPTH: usize = 3;
+
+/// How many items the preview pane lists per directory
+const PREVIEW_MAX_ITEMS: usize = 50;
+
+/// What the browser does to entries once the user confirmed it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrowserAction {
+    /// restores the marked entries, or the highlighted one, to their original location
+    Restore,
+    /// permanently deletes the marked entries, or the highlighted one
+    Remove,
+    /// permanently deletes every entry shown by the current filter
+    EmptySelected
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BrowserMode {
+    Browse,
+    Filter,
+    Confirm(BrowserAction)
+}
+
+/// A trash entry with the data `oscar list --sizes` shows for it
+struct BrowserEntry {
+    trash_info: TrashInfo,
+    /// Directories are not walked to keep the browser responsive, so their size is only known when it is cached
+    size: Option<u64>,
+    entry_type: Option<TrashEntryType>
+}
+
+impl BrowserEntry {
+    fn new(trash_info: TrashInfo) -> Self {
+        BrowserEntry {
+            size: cached_trash_entry_size(&trash_info).ok().flatten(),
+            entry_type: trash_info.entry_type().ok(),
+            trash_info
+        }
+    }
+}
+
+/// A full screen trash browser. It is driven by `handle_key` and rendered by `draw`, `run_trash_browser` wires both
+/// to the terminal.
+pub struct TrashBrowser {
+    filter: TrashFilter,
+    entries: Vec<BrowserEntry>,
+    /// Indices into `entries` of the entries matching `search`, in display order
+    shown: Vec<usize>,
+    marked: HashSet<usize>,
+    search: String,
+    mode: BrowserMode,
+    table_state: TableState,
+    status: Option<String>,
+    quit: bool
+}
+
+impl TrashBrowser {
+    /// Opens the browser on the entries of every trash directory matching `filter`
+    pub fn new(filter: TrashFilter) -> Result<Self> {
+        let mut browser = TrashBrowser {
+            filter,
+            entries: vec![],
+            shown: vec![],
+            marked: HashSet::new(),
+            search: String::new(),
+            mode: BrowserMode::Browse,
+            table_state: TableState::default(),
+            status: None,
+            quit: false
+        };
+        browser.reload()?;
+
+        Ok(browser)
+    }
+
+    /// Whether the user asked to leave the browser
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// The entries currently listed, in display order
+    pub fn shown_entries(&self) -> Vec<&TrashInfo> {
+        self.shown.iter().map(|&index| &self.entries[index].trash_info).collect()
+    }
+
+    /// The message describing the outcome of the last action, if any
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    fn reload(&mut self) -> Result<()> {
+        let mut trash_contents = self.filter.apply(get_all_trash_contents()?);
+        trash_contents.sort_by_key(|trash_info| std::cmp::Reverse(trash_info.deletion_date));
+
+        self.entries = trash_contents.into_iter().map(BrowserEntry::new).collect();
+        self.marked.clear();
+        self.apply_search();
+
+        Ok(())
+    }
+
+    /// Lists the entries whose original path fuzzy matches the search, best matches first. Without a search the
+    /// most recently deleted come first.
+    fn apply_search(&mut self) {
+        let matcher = original_path_matcher();
+        let mut scored: Vec<(usize, i64)> = self.entries.iter().enumerate().filter_map(|(index, entry)| {
+            if self.search.is_empty() {
+                Some((index, 0))
+            } else {
+                fuzzy_match_original_path(&matcher, &entry.trash_info, &self.search).map(|score| (index, score))
+            }
+        }).collect();
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+
+        self.shown = scored.into_iter().map(|(index, _)| index).collect();
+        self.table_state.select(if self.shown.is_empty() { None } else { Some(0) });
+    }
+
+    fn highlighted(&self) -> Option<usize> {
+        self.table_state.selected().and_then(|position| self.shown.get(position).copied())
+    }
+
+    /// The entries an action applies to: the shown entries that are marked, or the highlighted one
+    fn targets(&self, action: BrowserAction) -> Vec<usize> {
+        match action {
+            BrowserAction::EmptySelected => self.shown.clone(),
+            BrowserAction::Restore | BrowserAction::Remove => {
+                let marked: Vec<usize> = self.shown.iter().copied().filter(|index| self.marked.contains(index)).collect();
+                if marked.is_empty() {
+                    self.highlighted().into_iter().collect()
+                } else {
+                    marked
+                }
+            }
+        }
+    }
+
+    fn select(&mut self, position: usize) {
+        if !self.shown.is_empty() {
+            self.table_state.select(Some(position.min(self.shown.len() - 1)));
+        }
+    }
+
+    fn move_cursor(&mut self, offset: isize) {
+        self.select(self.table_state.selected().unwrap_or(0).saturating_add_signed(offset));
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+
+        if self.mode != BrowserMode::Filter {
+            self.status = None;
+        }
+
+        match self.mode {
+            BrowserMode::Browse => self.handle_browse_key(key),
+            BrowserMode::Filter => self.handle_filter_key(key),
+            BrowserMode::Confirm(action) => {
+                self.mode = BrowserMode::Browse;
+                if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                    self.run_action(action);
+                }
+            }
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc if self.search.is_empty() => self.quit = true,
+            KeyCode::Esc => {
+                self.search.clear();
+                self.apply_search();
+            },
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+            KeyCode::PageUp => self.move_cursor(-10),
+            KeyCode::PageDown => self.move_cursor(10),
+            KeyCode::Home | KeyCode::Char('g') => self.select(0),
+            KeyCode::End | KeyCode::Char('G') => self.select(usize::MAX),
+            KeyCode::Char('/') => self.mode = BrowserMode::Filter,
+            KeyCode::Char(' ') => {
+                if let Some(index) = self.highlighted() {
+                    if !self.marked.remove(&index) {
+                        self.marked.insert(index);
+                    }
+                    self.move_cursor(1);
+                }
+            },
+            KeyCode::Char('a') => {
+                if self.shown.iter().all(|index| self.marked.contains(index)) {
+                    self.marked.clear();
+                } else {
+                    self.marked.extend(self.shown.iter().copied());
+                }
+            },
+            KeyCode::Char('r') => self.confirm(BrowserAction::Restore),
+            KeyCode::Char('d') | KeyCode::Delete => self.confirm(BrowserAction::Remove),
+            KeyCode::Char('E') => self.confirm(BrowserAction::EmptySelected),
+            _ => ()
+        }
+    }
+
+    fn handle_filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => self.mode = BrowserMode::Browse,
+            KeyCode::Esc => {
+                self.mode = BrowserMode::Browse;
+                self.search.clear();
+                self.apply_search();
+            },
+            KeyCode::Backspace => {
+                self.search.pop();
+                self.apply_search();
+            },
+            KeyCode::Char(c) => {
+                self.search.push(c);
+                self.apply_search();
+            },
+            _ => ()
+        }
+    }
+
+    fn confirm(&mut self, action: BrowserAction) {
+        if self.targets(action).is_empty() {
+            self.status = Some(String::from("No entries to act on"));
+        } else {
+            self.mode = BrowserMode::Confirm(action);
+        }
+    }
+
+    fn run_action(&mut self, action: BrowserAction) {
+        let targets: Vec<TrashInfo> = self.targets(action).into_iter().map(|index| self.entries[index].trash_info.clone()).collect();
+
+        let results: Vec<Result<()>> = match action {
+            BrowserAction::Restore => targets.iter().map(|trash_info| {
+                trash_restore_to(trash_info, &trash_info.full_path, &RestoreOptions::default()).map(|_| ())
+            }).collect(),
+            BrowserAction::Remove | BrowserAction::EmptySelected => {
+                trash_remove_many(&targets).into_iter().map(|(_, result)| result).collect()
+            }
+        };
+
+        let verb = if action == BrowserAction::Restore { "Restored" } else { "Removed" };
+        let succeeded = results.iter().filter(|result| result.is_ok()).count();
+        let mut status = format!("{verb} {}", item_count(succeeded));
+        if let Some((trash_info, Err(error))) = targets.iter().zip(&results).find(|(_, result)| result.is_err()) {
+            status.push_str(&format!(", {} failed ({}: {error})", results.len() - succeeded, trash_info.full_path.display()));
+        }
+        self.status = Some(status);
+
+        // keep the cursor where it was, the entries acted on are gone
+        let position = self.table_state.selected().unwrap_or(0);
+        if let Err(error) = self.reload() {
+            self.status = Some(format!("Failed to read the trash: {error}"));
+        }
+        self.select(position);
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [title_area, main_area, status_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1)
+        ]).areas(frame.area());
+        let [list_area, preview_area] = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(main_area);
+
+        let title = match self.mode {
+            BrowserMode::Filter => format!("Filter: {}_", self.search),
+            _ if !self.search.is_empty() => format!("Filter: {}", self.search),
+            _ => String::from("oscar trash browser")
+        };
+        frame.render_widget(Line::from(title), title_area);
+
+        self.draw_entries(frame, list_area);
+        self.draw_preview(frame, preview_area);
+
+        let help = "↑↓ move  / filter  space mark  a mark all  r restore  d remove  E empty shown  q quit";
+        let status = self.status.as_deref().unwrap_or(help);
+        frame.render_widget(Line::from(status), status_area);
+
+        if let BrowserMode::Confirm(action) = self.mode {
+            self.draw_confirmation(frame, action);
+        }
+    }
+
+    fn draw_entries(&mut self, frame: &mut Frame, area: Rect) {
+        let header = Row::new(["", "Name", "Original Path", "Deletion Date", "Size", "Type"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let rows = self.shown.iter().map(|&index| {
+            let entry = &self.entries[index];
+            Row::new([
+                Cell::from(if self.marked.contains(&index) { "*" } else { " " }),
+                Cell::from(entry.trash_info.path.to_string_lossy().into_owned()),
+                Cell::from(entry.trash_info.full_path.display().to_string()),
+                Cell::from(entry.trash_info.deletion_date.to_string()),
+                Cell::from(entry.size.map(human_readable_size).unwrap_or_default()),
+                Cell::from(entry.entry_type.map(|entry_type| entry_type.to_string()).unwrap_or_default())
+            ])
+        });
+
+        let widths = [
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Fill(2),
+            Constraint::Length(19),
+            Constraint::Length(10),
+            Constraint::Length(7)
+        ];
+
+        let title = format!(" {} of {} ", item_count(self.shown.len()), self.entries.len());
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title(title))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn draw_preview(&self, frame: &mut Frame, area: Rect) {
+        let (title, preview) = match self.highlighted() {
+            Some(index) => {
+                let trash_info = &self.entries[index].trash_info;
+                (format!(" {} ", trash_info.path.to_string_lossy()), trash_entry_preview(trash_info))
+            },
+            None => (String::from(" Preview "), String::from("No entries"))
+        };
+
+        let paragraph = Paragraph::new(preview)
+            .block(Block::bordered().title(title))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_confirmation(&self, frame: &mut Frame, action: BrowserAction) {
+        let count = item_count(self.targets(action).len());
+        let question = match action {
+            BrowserAction::Restore => format!("Restore {count} to the original location?"),
+            BrowserAction::Remove => format!("Permanently delete {count}? This action is irreversible."),
+            BrowserAction::EmptySelected => format!("Permanently delete all {count} shown? This action is irreversible.")
+        };
+
+        let [area] = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(frame.area());
+        let [area] = Layout::vertical([Constraint::Length(4)]).flex(Flex::Center).areas(area);
+
+        let dialog = Paragraph::new(vec![Line::from(question), Line::from("y: yes, any other key: no")])
+            .block(Block::bordered().title(" Confirm "))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(Clear, area);
+        frame.render_widget(dialog, area);
+    }
+}
+
+/// The tree of a directory, at most `depth` levels deep
+fn preview_tree(path: &Path, label: String, depth: usize) -> Tree<String> {
+    let mut root = Tree::new(label);
+    if depth == 0 {
+        return root;
+    }
+
+    let mut children: Vec<_> = match read_dir(path) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).collect(),
+        Err(error) => {
+            root.push(Tree::new(error.to_string()));
+            return root;
+        }
+    };
+    children.sort_by_key(|entry| entry.file_name());
+
+    for entry in children.iter().take(PREVIEW_MAX_ITEMS) {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            root.push(preview_tree(&entry.path(), name, depth - 1));
+        } else {
+            root.push(Tree::new(name));
+        }
+    }
+
+    if children.len() > PREVIEW_MAX_ITEMS {
+        root.push(Tree::new(format!("… {} more", children.len() - PREVIEW_MAX_ITEMS)));
+    }
+
+    root
+}
+
+/// What the preview pane shows for an entry: the tree of a directory, the target of a symlink or the beginning of a
+/// text file
+pub fn trash_entry_preview(trash_info: &TrashInfo) -> String {
+    let trash_file_path = trash_info.trash_file_path();
+
+    match trash_info.entry_type() {
+        Ok(TrashEntryType::Directory) => preview_tree(&trash_file_path, String::from("."), PREVIEW_MAX_DEPTH).to_string(),
+        Ok(TrashEntryType::Symlink) => match read_link(&trash_file_path) {
+            Ok(target) => format!("Symlink to {}", target.display()),
+            Err(error) => error.to_string()
+        },
+        Ok(TrashEntryType::File) => {
+            let mut contents = vec![];
+            match File::open(&trash_file_path).and_then(|file| file.take(PREVIEW_MAX_BYTES).read_to_end(&mut contents)) {
+                Ok(_) if contents.contains(&0) => String::from("Binary file"),
+                Ok(_) => String::from_utf8_lossy(&contents).into_owned(),
+                Err(error) => error.to_string()
+            }
+        },
+        Err(error) => error.to_string()
+    }
+}
+
+/// Runs the browser in the terminal until the user quits
+pub fn run_trash_browser(filter: TrashFilter) -> Result<()> {
+    let mut browser = TrashBrowser::new(filter)?;
+
+    let mut terminal = ratatui::try_init()?;
+    let result = browse(&mut terminal, &mut browser);
+    ratatui::try_restore()?;
+
+    result
+}
+
+fn browse(terminal: &mut DefaultTerminal, browser: &mut TrashBrowser) -> Result<()> {
+    while !browser.should_quit() {
+        terminal.draw(|frame| browser.draw(frame))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                browser.handle_key(key);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,5 +1,10 @@
+#![allow(dead_code)]
+
 use std::{
     env::{set_var, temp_dir},
+    ffi::OsString,
+    fs::{create_dir_all, remove_dir_all},
+    path::PathBuf,
     process::Command
 };
 
@@ -10,6 +15,15 @@ pub fn setup_xdg_data_home() {
     set_var("XDG_DATA_HOME", temp_dir());
 }
 
+/// Creates an empty `name` directory in the temporary directory, removing whatever a previous run left there
+pub fn setup_test_dir(name: &str) -> PathBuf {
+    let test_dir = temp_dir().join(name);
+    let _ = remove_dir_all(&test_dir);
+    create_dir_all(&test_dir).unwrap();
+
+    test_dir
+}
+
 pub fn remove_trash_file_hierarchy() {
     if let Some(home_trash_dir) = freedesktop_home_trash_dir() {
         let _ = Command::new("rm")
@@ -33,8 +47,19 @@ pub fn test_file(is_file: bool) -> String {
 
 pub fn test_file_trash_entry(is_file: bool) -> TrashInfo {
     TrashInfo {
-        path: test_file(is_file), 
-        full_path: format!("/tmp/{}", test_file(is_file)), 
-        deletion_date: test_file_date()
+        path: OsString::from(test_file(is_file)), 
+        full_path: PathBuf::from(format!("/tmp/{}", test_file(is_file))), 
+        deletion_date: test_file_date(),
+        trash_dir: freedesktop_home_trash_dir().unwrap()
     }
-}
\ No newline at end of file
+}
+
+/// A home trash entry for a file deleted from `full_path` `days_ago` days before `test_file_date`
+pub fn test_trash_entry(full_path: &str, days_ago: i64) -> TrashInfo {
+    TrashInfo {
+        path: PathBuf::from(full_path).file_name().unwrap().to_owned(),
+        full_path: PathBuf::from(full_path),
+        deletion_date: test_file_date() - chrono::Duration::days(days_ago),
+        trash_dir: freedesktop_home_trash_dir().unwrap()
+    }
+}
@@ -1,7 +1,9 @@
 use std::{
     env::{set_var, remove_var, temp_dir}, 
-    fs::{create_dir_all, exists}, 
-    path::Path, 
+    ffi::OsString,
+    fs::{create_dir_all, exists, remove_dir_all, set_permissions, Permissions}, 
+    os::unix::fs::{symlink, PermissionsExt},
+    path::{Path, PathBuf}, 
     process::Command
 };
 
@@ -91,18 +93,18 @@ fn test_add_trashinfo_extension_to_file_without_extension() {
 fn test_create_home_trash_info_dir_if_not_exists() {
     setup_xdg_data_home();
 
-    if let Ok(_) = create_home_trash_info_dir_if_not_exists() {
+    if create_home_trash_info_dir_if_not_exists().is_ok() {
         if let Some(home_trash_info_dir) = freedesktop_home_trash_info_dir() {
             if let Ok(dir_exists) = exists(&home_trash_info_dir) {
                 assert!(dir_exists);
             } else {
-                assert!(false)
+                panic!()
             }
         } else {
-            assert!(false)
+            panic!()
         }
     } else {
-        assert!(false)
+        panic!()
     }
 
     remove_trash_file_hierarchy();
@@ -113,18 +115,18 @@ fn test_create_home_trash_info_dir_if_not_exists() {
 fn test_create_home_trash_files_dir_if_not_exists() {
     setup_xdg_data_home();
 
-    if let Ok(_) = create_home_trash_files_dir_if_not_exists() {
+    if create_home_trash_files_dir_if_not_exists().is_ok() {
         if let Some(home_trash_files_dir) = freedesktop_home_trash_files_dir() {
             if let Ok(dir_exists) = exists(&home_trash_files_dir) {
                 assert!(dir_exists);
             } else {
-                assert!(false)
+                panic!()
             }
         } else {
-            assert!(false)
+            panic!()
         }
     } else {
-        assert!(false)
+        panic!()
     }
 
     remove_trash_file_hierarchy();
@@ -137,48 +139,132 @@ fn test_get_home_trash_contents() {
 
     setup_xdg_data_home();
     if let Some(home_trash_info_dir) = freedesktop_home_trash_info_dir() {
-        if let Ok(_) = create_dir_all(&home_trash_info_dir) {
+        if create_dir_all(&home_trash_info_dir).is_ok() {
             if let Some(home_trash_files_dir) = freedesktop_home_trash_files_dir() {
-                if let Ok(_) = create_dir_all(&home_trash_files_dir) {
+                if create_dir_all(&home_trash_files_dir).is_ok() {
                     let _ = Command::new("touch")
-                        .arg(&home_trash_files_dir.join(&test_file))
+                        .arg(home_trash_files_dir.join(&test_file))
                         .output();
 
                     let mut trashinfo = Ini::new();
-                    if let Ok(_) = trashinfo.read(format!("[Trash Info]
+                    if trashinfo.read(format!("[Trash Info]
                         Path=/tmp/{test_file}
                         DeletionDate=2004-08-31T22:32:08"
-                    )) {
-                        if let Ok(_) = trashinfo.write(
-                            &home_trash_info_dir.join(with_trashinfo_extension(&Path::new(&test_file).to_path_buf()))
-                        ) {
+                    )).is_ok() {
+                        if trashinfo.write(
+                            home_trash_info_dir.join(with_trashinfo_extension(Path::new(&test_file)))
+                        ).is_ok() {
                             if let Ok(trash_contents) = get_home_trash_contents() {
                                 assert_eq!(
                                     trash_contents, 
                                     vec![
                                         TrashInfo { 
-                                            path: test_file.clone(), 
-                                            full_path: format!("/tmp/{}", test_file.clone()), 
-                                            deletion_date: test_file_date()
+                                            path: OsString::from(&test_file), 
+                                            full_path: PathBuf::from(format!("/tmp/{}", test_file)), 
+                                            deletion_date: test_file_date(),
+                                            trash_dir: freedesktop_home_trash_dir().unwrap()
                                         }
                                     ]
                                 );
                                 remove_trash_file_hierarchy();
                             } else {
-                                assert!(false)
+                                panic!()
                             }
                         } else {
-                            assert!(false)
+                            panic!()
                         }
                     } else {
-                        assert!(false)
+                        panic!()
                     }
                 } else {
-                    assert!(false)
+                    panic!()
                 }
             }
         } else {
-            assert!(false)
+            panic!()
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+#[parallel]
+fn test_topdir_trash_dir_with_valid_shared_trash() {
+    let top_dir = setup_test_dir("oscar-topdir-sticky");
+    create_dir_all(top_dir.join(".Trash")).unwrap();
+    set_permissions(top_dir.join(".Trash"), Permissions::from_mode(0o1777)).unwrap();
+
+    assert_eq!(
+        freedesktop_topdir_trash_dir(&top_dir),
+        top_dir.join(".Trash").join(current_uid().to_string())
+    );
+
+    remove_dir_all(&top_dir).unwrap();
+}
+
+#[test]
+#[parallel]
+fn test_topdir_trash_dir_without_sticky_bit() {
+    let top_dir = setup_test_dir("oscar-topdir-not-sticky");
+    create_dir_all(top_dir.join(".Trash")).unwrap();
+    set_permissions(top_dir.join(".Trash"), Permissions::from_mode(0o777)).unwrap();
+
+    assert_eq!(freedesktop_topdir_trash_dir(&top_dir), top_dir.join(format!(".Trash-{}", current_uid())));
+
+    remove_dir_all(&top_dir).unwrap();
+}
+
+#[test]
+#[parallel]
+fn test_topdir_trash_dir_with_symlinked_shared_trash() {
+    let top_dir = setup_test_dir("oscar-topdir-symlink");
+    create_dir_all(top_dir.join("elsewhere")).unwrap();
+    set_permissions(top_dir.join("elsewhere"), Permissions::from_mode(0o1777)).unwrap();
+    symlink(top_dir.join("elsewhere"), top_dir.join(".Trash")).unwrap();
+
+    assert_eq!(freedesktop_topdir_trash_dir(&top_dir), top_dir.join(format!(".Trash-{}", current_uid())));
+
+    remove_dir_all(&top_dir).unwrap();
+}
+
+#[test]
+#[parallel]
+fn test_existing_topdir_trash_dirs_rejects_symlinks() {
+    let top_dir = setup_test_dir("oscar-topdir-existing");
+    assert!(existing_topdir_trash_dirs(&top_dir).is_empty());
+
+    create_dir_all(top_dir.join("elsewhere")).unwrap();
+    symlink(top_dir.join("elsewhere"), freedesktop_topdir_user_trash_dir(&top_dir)).unwrap();
+    assert!(existing_topdir_trash_dirs(&top_dir).is_empty());
+
+    std::fs::remove_file(freedesktop_topdir_user_trash_dir(&top_dir)).unwrap();
+    create_dir_all(freedesktop_topdir_user_trash_dir(&top_dir)).unwrap();
+    assert_eq!(existing_topdir_trash_dirs(&top_dir), vec![freedesktop_topdir_user_trash_dir(&top_dir)]);
+
+    remove_dir_all(&top_dir).unwrap();
+}
+
+#[test]
+#[serial]
+fn test_trash_dir_for_path_fails_without_topdir_trash() {
+    setup_xdg_data_home();
+
+    // nothing can be created at the top of /proc, which is never on the same file system as the home trash
+    let error = trash_dir_for_path(Path::new("/proc/self/status")).unwrap_err();
+    assert!(error.to_string().starts_with("cannot create a trash directory in /proc: "));
+}
+
+#[test]
+#[parallel]
+fn test_find_mount_point() {
+    assert_eq!(find_mount_point(Path::new("/proc/self")).unwrap(), Path::new("/proc").to_path_buf());
+}
+
+#[test]
+#[parallel]
+fn test_trash_dir_top_dir() {
+    let top_dir = temp_dir().join("oscar-top-dir");
+
+    assert_eq!(trash_dir_top_dir(&freedesktop_topdir_user_trash_dir(&top_dir)), Some(top_dir.clone()));
+    assert_eq!(trash_dir_top_dir(&freedesktop_topdir_shared_trash_dir(&top_dir)), Some(top_dir.clone()));
+    assert_eq!(trash_dir_top_dir(&top_dir.join("Trash")), None);
+}
@@ -0,0 +1,74 @@
+use std::{
+    ffi::OsString,
+    fs::{create_dir_all, exists, read_to_string, write, File, FileTimes},
+    io::Result,
+    time::{Duration, SystemTime}
+};
+
+use common::{
+    remove_trash_file_hierarchy,
+    setup_test_dir,
+    setup_xdg_data_home
+};
+use oscar::{
+    actions::{trash_empty::trash_empty, trash_put::trash_put, trash_remove::trash_remove},
+    common::{freedesktop_home_trash_dir, get_home_trash_contents},
+    directory_sizes::{cached_trash_entry_size, directory_sizes_path, disk_usage, parse_directory_sizes, read_directory_sizes, trash_entry_size, DirectorySize}
+};
+use serial_test::serial;
+
+mod common;
+
+#[test]
+fn test_parse_directory_sizes() {
+    let directory_sizes = parse_directory_sizes("4096 1093991528 photos\n16384 1093991529 holiday%20photos\nnot a line\n");
+
+    assert_eq!(directory_sizes, vec![
+        DirectorySize { size: 4096, trash_info_mtime: 1093991528, name: OsString::from("photos") },
+        DirectorySize { size: 16384, trash_info_mtime: 1093991529, name: OsString::from("holiday photos") },
+    ]);
+}
+
+#[test]
+#[serial]
+fn test_directory_sizes_cache_is_maintained() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir("oscar sizes");
+    create_dir_all(test_dir.join("nested"))?;
+    write(test_dir.join("nested").join("data.bin"), vec![0; 10_000])?;
+    let size = disk_usage(&test_dir)?;
+
+    trash_put(&test_dir)?;
+
+    let trash_dir = freedesktop_home_trash_dir().unwrap();
+    assert!(read_to_string(directory_sizes_path(&trash_dir))?.ends_with(" oscar%20sizes\n"));
+    assert_eq!(read_directory_sizes(&trash_dir)?[0].size, size);
+
+    // a fresh line is trusted without walking the directory...
+    let mut directory_sizes = read_to_string(directory_sizes_path(&trash_dir))?;
+    directory_sizes.replace_range(..size.to_string().len(), "42");
+    write(directory_sizes_path(&trash_dir), directory_sizes)?;
+
+    let trash_entry = get_home_trash_contents()?.pop().unwrap();
+    assert_eq!(trash_entry_size(&trash_entry)?, 42);
+    assert_eq!(cached_trash_entry_size(&trash_entry)?, Some(42));
+
+    // ...while a stale one is not
+    File::options().write(true).open(trash_entry.trash_info_path())?
+        .set_times(FileTimes::new().set_modified(SystemTime::now() + Duration::from_secs(60)))?;
+    assert_eq!(trash_entry_size(&trash_entry)?, size);
+    assert_eq!(cached_trash_entry_size(&trash_entry)?, None);
+
+    trash_remove(&trash_entry)?;
+    assert!(read_directory_sizes(&trash_dir)?.is_empty());
+
+    create_dir_all(&test_dir)?;
+    trash_put(&test_dir)?;
+    trash_empty()?;
+    assert!(!exists(directory_sizes_path(&trash_dir))?);
+
+    remove_trash_file_hierarchy();
+    Ok(())
+}
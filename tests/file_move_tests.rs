@@ -0,0 +1,148 @@
+use std::{
+    fs::{create_dir_all, exists, metadata, read_link, read_to_string, remove_dir_all, set_permissions, symlink_metadata, write, File, FileTimes, Permissions},
+    io::{Error, ErrorKind, Result},
+    os::unix::fs::{symlink, MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime}
+};
+
+use common::setup_test_dir;
+use oscar::file_move::{move_by_copying, move_by_copying_with, move_path, IncompleteMoveError};
+use serial_test::parallel;
+
+mod common;
+
+/// Creates `dir/tree` containing a file with a fixed mtime, a read-only file, a nested directory and a symlink
+fn create_test_tree(dir: &Path) -> Result<PathBuf> {
+    let tree = dir.join("tree");
+    create_dir_all(tree.join("nested"))?;
+    write(tree.join("notes.txt"), "notes")?;
+    write(tree.join("nested").join("readonly.txt"), "readonly")?;
+    set_permissions(tree.join("nested").join("readonly.txt"), Permissions::from_mode(0o444))?;
+    symlink("notes.txt", tree.join("link"))?;
+
+    File::options().write(true).open(tree.join("notes.txt"))?
+        .set_times(FileTimes::new().set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000)))?;
+
+    Ok(tree)
+}
+
+fn assert_test_tree_copied(tree: &Path) -> Result<()> {
+    assert_eq!(read_to_string(tree.join("notes.txt"))?, "notes");
+    assert_eq!(metadata(tree.join("notes.txt"))?.mtime(), 1_000_000_000);
+    assert_eq!(read_to_string(tree.join("nested").join("readonly.txt"))?, "readonly");
+    assert_eq!(metadata(tree.join("nested").join("readonly.txt"))?.permissions().mode() & 0o777, 0o444);
+    assert!(symlink_metadata(tree.join("link"))?.is_symlink());
+    assert_eq!(read_link(tree.join("link"))?, PathBuf::from("notes.txt"));
+
+    Ok(())
+}
+
+#[test]
+#[parallel]
+fn test_move_path_same_file_system() -> Result<()> {
+    let test_dir = setup_test_dir("oscar-move-same-fs");
+    let tree = create_test_tree(&test_dir)?;
+
+    move_path(&tree, test_dir.join("moved"))?;
+
+    assert!(!exists(&tree)?);
+    assert_test_tree_copied(&test_dir.join("moved"))?;
+
+    remove_dir_all(&test_dir)
+}
+
+#[test]
+#[parallel]
+fn test_move_by_copying_preserves_tree() -> Result<()> {
+    let test_dir = setup_test_dir("oscar-move-by-copying");
+    let tree = create_test_tree(&test_dir)?;
+
+    move_by_copying(&tree, test_dir.join("copied"))?;
+
+    assert!(!exists(&tree)?);
+    assert_test_tree_copied(&test_dir.join("copied"))?;
+
+    remove_dir_all(&test_dir)
+}
+
+#[test]
+#[parallel]
+fn test_move_by_copying_failure_leaves_source_untouched() -> Result<()> {
+    let test_dir = setup_test_dir("oscar-move-by-copying-failure");
+    let tree = create_test_tree(&test_dir)?;
+    Command::new("mkfifo").arg(tree.join("fifo")).output()?;
+
+    assert!(move_by_copying(&tree, test_dir.join("copied")).is_err());
+
+    assert_test_tree_copied(&tree)?;
+    assert!(!exists(test_dir.join("copied"))?);
+    // nothing but the source tree should be left in the test directory
+    assert_eq!(std::fs::read_dir(&test_dir)?.count(), 1);
+
+    remove_dir_all(&test_dir)
+}
+
+#[test]
+#[parallel]
+fn test_move_by_copying_keeps_existing_file_with_temporary_name() -> Result<()> {
+    let test_dir = setup_test_dir("oscar-move-by-copying-taken");
+    let tree = create_test_tree(&test_dir)?;
+    let taken = test_dir.join(format!(".copied.oscar-partial-{}", std::process::id()));
+    write(&taken, "trashed on purpose")?;
+
+    assert_eq!(move_by_copying(&tree, test_dir.join("copied")).unwrap_err().kind(), ErrorKind::AlreadyExists);
+
+    assert_eq!(read_to_string(&taken)?, "trashed on purpose");
+    assert_test_tree_copied(&tree)?;
+    assert!(!exists(test_dir.join("copied"))?);
+
+    remove_dir_all(&test_dir)
+}
+
+#[test]
+#[parallel]
+fn test_move_by_copying_reports_source_not_removed() -> Result<()> {
+    let test_dir = setup_test_dir("oscar-move-by-copying-incomplete");
+    let tree = create_test_tree(&test_dir)?;
+
+    let result = move_by_copying_with(&tree, test_dir.join("copied"), |_| Err(Error::from(ErrorKind::PermissionDenied)));
+
+    // the copy is complete and in place, only the source is left behind
+    let error = result.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    let incomplete_move_error = IncompleteMoveError::from_io_error(&error).unwrap();
+    assert_eq!(incomplete_move_error.to, test_dir.join("copied"));
+    assert_test_tree_copied(&test_dir.join("copied"))?;
+    assert!(exists(tree.join("nested").join("readonly.txt"))?);
+
+    remove_dir_all(&test_dir)
+}
+
+#[test]
+#[parallel]
+fn test_move_path_across_file_systems() -> Result<()> {
+    let other_file_system = Path::new("/dev/shm");
+    let test_dir = setup_test_dir("oscar-move-across-fs");
+    let tree = create_test_tree(&test_dir)?;
+
+    // the copy, verify and delete fallback is always exercised, through a real EXDEV when a tmpfs is available
+    // on another device
+    let destination = if other_file_system.is_dir() && metadata(other_file_system)?.dev() != metadata(&test_dir)?.dev() {
+        let destination = other_file_system.join("oscar-move-across-fs");
+        let _ = remove_dir_all(&destination);
+        move_path(&tree, &destination)?;
+        destination
+    } else {
+        let destination = test_dir.join("moved");
+        move_by_copying(&tree, &destination)?;
+        destination
+    };
+
+    assert!(!exists(&tree)?);
+    assert_test_tree_copied(&destination)?;
+
+    remove_dir_all(&destination)?;
+    remove_dir_all(&test_dir)
+}
@@ -0,0 +1,77 @@
+use std::{
+    env::temp_dir,
+    fs::{create_dir_all, remove_dir_all, write},
+    io::Result,
+    path::{Path, PathBuf}
+};
+
+use common::{remove_trash_file_hierarchy, setup_xdg_data_home};
+use oscar::{
+    common::{current_uid, freedesktop_home_trash_dir, get_all_trash_contents_in, topdir_trash_dirs, with_trashinfo_extension},
+    mount_table::*
+};
+use serial_test::{parallel, serial};
+
+mod common;
+
+fn fixture_mount_table() -> Vec<MountEntry> {
+    read_mount_table(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mountinfo")).unwrap()
+}
+
+#[test]
+#[parallel]
+fn test_read_mount_table_fixture() {
+    assert_eq!(
+        fixture_mount_table(),
+        vec![
+            MountEntry { mount_point: PathBuf::from("/proc"), device_id: libc::makedev(0, 22) },
+            MountEntry { mount_point: PathBuf::from("/"), device_id: libc::makedev(254, 0) },
+            MountEntry { mount_point: PathBuf::from("/media/usb drive"), device_id: libc::makedev(8, 17) },
+            MountEntry { mount_point: PathBuf::from("/srv/data"), device_id: libc::makedev(8, 33) }
+        ]
+    );
+}
+
+#[test]
+#[parallel]
+fn test_find_mount_picks_deepest_mount_on_device() {
+    let mounts = fixture_mount_table();
+
+    let usb_mount = find_mount(&mounts, Path::new("/media/usb drive/photos/cat.png"), libc::makedev(8, 17));
+    assert_eq!(usb_mount.map(|mount| mount.mount_point.clone()), Some(PathBuf::from("/media/usb drive")));
+
+    let root_mount = find_mount(&mounts, Path::new("/media/usb drive/photos/cat.png"), libc::makedev(254, 0));
+    assert_eq!(root_mount.map(|mount| mount.mount_point.clone()), Some(PathBuf::from("/")));
+
+    assert_eq!(find_mount(&mounts, Path::new("/home/user"), libc::makedev(8, 33)), None);
+}
+
+#[test]
+#[serial]
+fn test_get_all_trash_contents_includes_topdir_trashes() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let top_dir = temp_dir().join("oscar-mount-table-top-dir");
+    let topdir_trash_dir = top_dir.join(format!(".Trash-{}", current_uid()));
+    create_dir_all(topdir_trash_dir.join("files"))?;
+    create_dir_all(topdir_trash_dir.join("info"))?;
+    write(topdir_trash_dir.join("files").join("report.pdf"), "")?;
+    write(
+        with_trashinfo_extension(&topdir_trash_dir.join("info").join("report.pdf")),
+        format!("[Trash Info]\nPath={}/report.pdf\nDeletionDate=2004-08-31T22:32:08\n", top_dir.display())
+    )?;
+
+    let mounts = vec![MountEntry { mount_point: top_dir.clone(), device_id: 0 }];
+    assert_eq!(topdir_trash_dirs(&mounts), vec![topdir_trash_dir.clone()]);
+
+    let trash_contents = get_all_trash_contents_in(&mounts)?;
+    assert_eq!(trash_contents.len(), 1);
+    assert_eq!(trash_contents[0].path, "report.pdf");
+    assert_eq!(trash_contents[0].trash_dir, topdir_trash_dir);
+    assert_ne!(Some(trash_contents[0].trash_dir.clone()), freedesktop_home_trash_dir());
+
+    remove_dir_all(&top_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use std::{
+    env::{remove_var, set_var, temp_dir, var},
+    fs::{remove_dir_all, write},
+    io::Result,
+    path::{Path, PathBuf}
+};
+
+use common::{remove_trash_file_hierarchy, setup_test_dir, setup_xdg_data_home};
+use oscar::{
+    actions::trash_put::{trash_put, trash_put_with_options, PutOptions},
+    common::{create_home_trash_dir_if_not_exists, freedesktop_home_trash_files_dir},
+    constants::OSCAR_PROTECTED_PATHS_VAR,
+    protected_paths::*
+};
+use serial_test::serial;
+
+mod common;
+
+fn protection_reason(path: &Path, additional_protected_paths: &[PathBuf]) -> Option<ProtectionReason> {
+    match check_not_protected(path, additional_protected_paths) {
+        Ok(_) => None,
+        Err(error) => ProtectedPathError::from_io_error(&error).map(|error| error.reason.clone())
+    }
+}
+
+#[test]
+#[serial]
+fn test_root_is_protected() {
+    assert_eq!(protection_reason(Path::new("/"), &[]), Some(ProtectionReason::FileSystemRoot));
+}
+
+#[test]
+#[serial]
+fn test_home_and_its_ancestors_are_protected() {
+    let home = var("HOME").ok();
+    set_var("HOME", "/tmp/oscar-protected-home/user");
+
+    assert_eq!(protection_reason(Path::new("/tmp/oscar-protected-home/user"), &[]), Some(ProtectionReason::HomeDirectory));
+    assert_eq!(protection_reason(Path::new("/tmp/oscar-protected-home"), &[]), Some(ProtectionReason::HomeDirectory));
+    assert_eq!(protection_reason(Path::new("/tmp/oscar-protected-home/user/notes.txt"), &[]), None);
+
+    match home {
+        Some(home) => set_var("HOME", home),
+        None => remove_var("HOME")
+    }
+}
+
+#[test]
+#[serial]
+fn test_trash_directories_are_protected() -> Result<()> {
+    setup_xdg_data_home();
+    create_home_trash_dir_if_not_exists()?;
+    let trash_files_dir = freedesktop_home_trash_files_dir().unwrap();
+
+    assert!(matches!(protection_reason(&trash_files_dir, &[]), Some(ProtectionReason::InsideTrash(_))));
+    assert!(matches!(protection_reason(&trash_files_dir.join("test.txt"), &[]), Some(ProtectionReason::InsideTrash(_))));
+    assert!(matches!(protection_reason(&temp_dir(), &[]), Some(ProtectionReason::ContainsTrash(_))));
+
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_mount_points_are_protected() {
+    assert_eq!(protection_reason(Path::new("/proc"), &[]), Some(ProtectionReason::MountPoint));
+}
+
+#[test]
+#[serial]
+fn test_configured_protected_paths() -> Result<()> {
+    setup_xdg_data_home();
+    let test_dir = setup_test_dir("oscar-configured-protected");
+    write(test_dir.join("keep.txt"), "")?;
+    write(test_dir.join("also-keep.txt"), "")?;
+
+    set_var(OSCAR_PROTECTED_PATHS_VAR, format!("/does/not/exist:{}", test_dir.join("keep.txt").display()));
+
+    let error = trash_put(test_dir.join("keep.txt")).unwrap_err();
+    assert_eq!(
+        ProtectedPathError::from_io_error(&error).map(|error| error.reason.clone()),
+        Some(ProtectionReason::Configured(test_dir.join("keep.txt")))
+    );
+
+    let options = PutOptions { protected_paths: vec![test_dir.join("also-keep.txt")], ..PutOptions::default() };
+    assert!(trash_put_with_options(test_dir.join("also-keep.txt"), &options).is_err());
+
+    // the protected file is inside this directory, so trashing the directory would take it along
+    assert!(trash_put(&test_dir).is_err());
+
+    let override_options = PutOptions { allow_protected: true, ..PutOptions::default() };
+    assert!(trash_put_with_options(test_dir.join("keep.txt"), &override_options).is_ok());
+
+    remove_var(OSCAR_PROTECTED_PATHS_VAR);
+    remove_dir_all(&test_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_io_errors_are_not_protected_path_errors() {
+    let error = trash_put("/tmp/oscar-does-not-exist.txt").unwrap_err();
+    assert!(ProtectedPathError::from_io_error(&error).is_none());
+}
@@ -0,0 +1,43 @@
+use oscar::string_encode::{decode_filename, encode_filename};
+
+#[test]
+fn test_encode_keeps_unreserved_characters_and_slashes() {
+    assert_eq!(encode_filename(b"/home/user/My-File_v1.0~.txt"), "/home/user/My-File_v1.0~.txt");
+}
+
+#[test]
+fn test_encode_reserved_characters() {
+    assert_eq!(
+        encode_filename(b"/tmp/a b,{c}|d%e#f?g\nh"),
+        "/tmp/a%20b%2C%7Bc%7D%7Cd%25e%23f%3Fg%0Ah"
+    );
+}
+
+#[test]
+fn test_encode_non_ascii_bytes() {
+    assert_eq!(encode_filename("/tmp/résumé.pdf".as_bytes()), "/tmp/r%C3%A9sum%C3%A9.pdf");
+    assert_eq!(encode_filename(b"/tmp/caf\xe9"), "/tmp/caf%E9");
+}
+
+#[test]
+fn test_decode_lowercase_escapes() {
+    assert_eq!(decode_filename("/tmp/r%c3%a9sum%c3%a9.pdf"), "/tmp/résumé.pdf".as_bytes());
+}
+
+#[test]
+fn test_decode_keeps_malformed_escapes() {
+    assert_eq!(decode_filename("/tmp/100%"), b"/tmp/100%");
+    assert_eq!(decode_filename("/tmp/%zz%4"), b"/tmp/%zz%4");
+}
+
+#[test]
+fn test_literal_percent_sequence_round_trips() {
+    assert_eq!(encode_filename(b"/tmp/my%20file"), "/tmp/my%2520file");
+    assert_eq!(decode_filename(&encode_filename(b"/tmp/my%20file")), b"/tmp/my%20file");
+}
+
+#[test]
+fn test_every_byte_round_trips() {
+    let path: Vec<u8> = (1..=u8::MAX).collect();
+    assert_eq!(decode_filename(&encode_filename(&path)), path);
+}
@@ -1,7 +1,7 @@
 use std::fs::read_dir;
 use std::path::Path;
 use std::process::Command;
-use std::io::{Error, ErrorKind, Result};
+use std::io::{Error, Result};
 
 use common::setup_xdg_data_home;
 use oscar::{
@@ -40,13 +40,13 @@ fn create_home_trash_hierarchy() -> Result<()> {
                         Err(error) => Err(error)
                     }
                 } else {
-                    Err(Error::new(ErrorKind::Other, "Failed to compute trash directory"))
+                    Err(Error::other("Failed to compute trash directory"))
                 }
             },
             Err(error) => Err(error)
         }
     } else {
-        Err(Error::new(ErrorKind::Other, "Failed to compute trash directory"))
+        Err(Error::other("Failed to compute trash directory"))
     }
 }
 
@@ -55,7 +55,7 @@ fn test_trash_empty() -> Result<()> {
     match create_home_trash_hierarchy() {
         Ok(_) => match trash_empty() {
             Ok(_) => {
-                if let Some(_) = freedesktop_home_trash_dir() {
+                if freedesktop_home_trash_dir().is_some() {
                     let home_trash_files_dir = freedesktop_home_trash_files_dir().unwrap();
                     let home_trash_info_dir = freedesktop_home_trash_info_dir().unwrap();
 
@@ -65,10 +65,10 @@ fn test_trash_empty() -> Result<()> {
                     if file_items_count == 0 && info_items_count == 0 {
                         Ok(())
                     } else {
-                        Err(Error::new(ErrorKind::Other, "Trash directories are not empty"))
+                        Err(Error::other("Trash directories are not empty"))
                     }
                 } else {
-                    Err(Error::new(ErrorKind::Other, "Failed to locate the home trash directory"))
+                    Err(Error::other("Failed to locate the home trash directory"))
                 }
             },
             Err(error) => Err(error)
@@ -0,0 +1,102 @@
+use std::{ffi::OsString, fs::{create_dir_all, remove_dir_all}, os::unix::fs::symlink, path::PathBuf};
+
+use chrono::NaiveDate;
+use common::{setup_test_dir, test_file_date, test_trash_entry};
+use glob::Pattern;
+use oscar::{
+    actions::trash_list::{sort_trash_contents, ListSort},
+    trash_filter::{parse_date, resolve_filter_dir, TrashFilter},
+    trash_info::{TrashEntryType, TrashInfo}
+};
+use regex::Regex;
+
+mod common;
+
+#[test]
+fn test_parse_date() {
+    let now = test_file_date();
+
+    assert_eq!(parse_date("2024-05-01", now), Ok(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(0, 0, 0).unwrap()));
+    assert_eq!(parse_date("2024-05-01 13:30", now), Ok(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(13, 30, 0).unwrap()));
+    assert_eq!(parse_date("2024-05-01T13:30:15", now), Ok(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(13, 30, 15).unwrap()));
+    assert_eq!(parse_date("3d", now), Ok(now - chrono::Duration::days(3)));
+    assert_eq!(parse_date("2w", now), Ok(now - chrono::Duration::weeks(2)));
+    assert_eq!(parse_date("12h", now), Ok(now - chrono::Duration::hours(12)));
+    assert!(parse_date("3y", now).is_err());
+    assert!(parse_date("d", now).is_err());
+    assert!(parse_date("yesterday", now).is_err());
+}
+
+#[test]
+fn test_trash_filter() {
+    let trash_contents = [
+        test_trash_entry("/home/user/notes.txt", 0),
+        test_trash_entry("/home/user/logs/app.log", 5),
+        test_trash_entry("/home/user/logs/old/app.log", 30),
+    ];
+    let names = |filter: &TrashFilter| trash_contents
+        .iter()
+        .filter(|trash_info| filter.matches(trash_info))
+        .map(|trash_info| trash_info.full_path.clone())
+        .collect::<Vec<_>>();
+
+    assert!(TrashFilter::default().is_empty());
+    assert_eq!(names(&TrashFilter::default()).len(), 3);
+
+    let filter = TrashFilter { name: Some(Pattern::new("*.log").unwrap()), ..TrashFilter::default() };
+    assert_eq!(names(&filter), vec![PathBuf::from("/home/user/logs/app.log"), PathBuf::from("/home/user/logs/old/app.log")]);
+
+    let filter = TrashFilter { regex: Some(Regex::new("^/home/user/logs/old/").unwrap()), ..TrashFilter::default() };
+    assert_eq!(names(&filter), vec![PathBuf::from("/home/user/logs/old/app.log")]);
+
+    let filter = TrashFilter { since: Some(test_file_date() - chrono::Duration::days(7)), ..TrashFilter::default() };
+    assert_eq!(names(&filter).len(), 2);
+
+    let filter = TrashFilter { before: Some(test_file_date() - chrono::Duration::days(7)), ..TrashFilter::default() };
+    assert_eq!(names(&filter), vec![PathBuf::from("/home/user/logs/old/app.log")]);
+
+    let filter = TrashFilter { in_dir: Some(PathBuf::from("/home/user/logs")), ..TrashFilter::default() };
+    assert_eq!(names(&filter), vec![PathBuf::from("/home/user/logs/app.log")]);
+
+    let filter = TrashFilter { in_dir: Some(PathBuf::from("/home/user/logs")), in_subdirs: true, ..TrashFilter::default() };
+    assert_eq!(names(&filter).len(), 2);
+
+    // the trashed files do not exist, so their type is unknown
+    let filter = TrashFilter { entry_type: Some(TrashEntryType::File), ..TrashFilter::default() };
+    assert!(names(&filter).is_empty());
+}
+
+#[test]
+fn test_sort_trash_contents() {
+    let mut trash_contents = vec![
+        test_trash_entry("/b/beta.txt", 2),
+        test_trash_entry("/c/alpha.txt", 0),
+        test_trash_entry("/a/gamma.txt", 1),
+    ];
+    let names = |trash_contents: &[TrashInfo]| trash_contents.iter().map(|t| t.path.clone()).collect::<Vec<OsString>>();
+
+    sort_trash_contents(&mut trash_contents, ListSort::Date, false);
+    assert_eq!(names(&trash_contents), ["alpha.txt", "gamma.txt", "beta.txt"]);
+
+    sort_trash_contents(&mut trash_contents, ListSort::Name, false);
+    assert_eq!(names(&trash_contents), ["alpha.txt", "beta.txt", "gamma.txt"]);
+
+    sort_trash_contents(&mut trash_contents, ListSort::Path, true);
+    assert_eq!(names(&trash_contents), ["alpha.txt", "beta.txt", "gamma.txt"]);
+
+    sort_trash_contents(&mut trash_contents, ListSort::Path, false);
+    assert_eq!(names(&trash_contents), ["gamma.txt", "beta.txt", "alpha.txt"]);
+}
+
+#[test]
+fn test_resolve_filter_dir() {
+    let test_dir = setup_test_dir("oscar-filter-dir");
+    create_dir_all(test_dir.join("real")).unwrap();
+    symlink(test_dir.join("real"), test_dir.join("link")).unwrap();
+
+    let real_dir = test_dir.canonicalize().unwrap().join("real");
+    assert_eq!(resolve_filter_dir(&test_dir.join("link")), real_dir);
+    assert_eq!(resolve_filter_dir(&test_dir.join("gone")), test_dir.join("gone"));
+
+    remove_dir_all(&test_dir).unwrap();
+}
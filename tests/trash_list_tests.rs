@@ -1,9 +1,19 @@
 mod common;
 
-use std::io::Error;
+use std::{
+    fs::{create_dir_all, remove_dir_all, write},
+    io::Error,
+    os::unix::fs::symlink
+};
 
-use common::{remove_trash_file_hierarchy, setup_xdg_data_home};
-use oscar::actions::trash_list::trash_list;
+use chrono::Duration;
+use common::{remove_trash_file_hierarchy, setup_test_dir, setup_xdg_data_home, test_file_date, test_file_trash_entry};
+use oscar::{
+    actions::{trash_list::{format_trash_contents, human_readable_size, relative_date, trash_contents_table, trash_list, trash_list_with_options, ListColumn, ListFormat, ListOptions}, trash_put::trash_put},
+    common::get_home_trash_contents,
+    directory_sizes::disk_usage,
+    trash_info::TrashEntryType
+};
 use serial_test::serial;
 
 #[test]
@@ -36,4 +46,142 @@ fn test_list_trash_rec_without_home_trash() -> Result<(), Error> {
         },
         Err(err) => Err(err)
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_human_readable_size() {
+    assert_eq!(human_readable_size(0), "0 B");
+    assert_eq!(human_readable_size(1023), "1023 B");
+    assert_eq!(human_readable_size(1536), "1.5 KiB");
+    assert_eq!(human_readable_size(40 * 1024 * 1024 * 1024), "40.0 GiB");
+}
+
+#[test]
+fn test_relative_date() {
+    let now = test_file_date();
+    assert_eq!(relative_date(now, now), "just now");
+    assert_eq!(relative_date(now - Duration::seconds(59), now), "just now");
+    assert_eq!(relative_date(now - Duration::minutes(1), now), "1 minute ago");
+    assert_eq!(relative_date(now - Duration::minutes(200), now), "3 hours ago");
+    assert_eq!(relative_date(now - Duration::days(2), now), "2 days ago");
+    assert_eq!(relative_date(now - Duration::days(15), now), "2 weeks ago");
+    assert_eq!(relative_date(now - Duration::days(400), now), "1 year ago");
+    assert_eq!(relative_date(now + Duration::minutes(5), now), "just now");
+}
+
+#[test]
+#[serial]
+fn test_list_trash_with_sizes() -> Result<(), Error> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir("oscar-list-sizes");
+    create_dir_all(test_dir.join("dir").join("nested"))?;
+    write(test_dir.join("file.txt"), "file")?;
+    write(test_dir.join("dir").join("nested").join("deep.txt"), "deep")?;
+    symlink("file.txt", test_dir.join("link"))?;
+
+    for name in ["dir", "file.txt", "link"] {
+        trash_put(test_dir.join(name))?;
+    }
+
+    let mut entry_types = get_home_trash_contents()?
+        .iter()
+        .map(|trash_info| (trash_info.path.clone(), trash_info.entry_type().unwrap()))
+        .collect::<Vec<_>>();
+    entry_types.sort_by(|a, b| a.0.cmp(&b.0));
+
+    assert_eq!(entry_types, vec![
+        ("dir".into(), TrashEntryType::Directory),
+        ("file.txt".into(), TrashEntryType::File),
+        ("link".into(), TrashEntryType::Symlink),
+    ]);
+
+    let options = ListOptions { sizes: true, bytes: true, ..ListOptions::default() };
+    let trash_contents = get_home_trash_contents()?;
+    let table = trash_contents_table(&trash_contents, &options);
+    let row = |name: &str| table.lines().find(|line| line.starts_with(&format!("│ {} ", name))).unwrap().to_string();
+    let cells = |name: &str| row(name).split('│').map(|cell| cell.trim().to_string()).filter(|cell| !cell.is_empty()).collect::<Vec<_>>();
+
+    let mut total_size = 0;
+    for (name, entry_type, item_count) in [("dir", "dir", Some("2")), ("file.txt", "file", None), ("link", "symlink", None)] {
+        let trash_info = trash_contents.iter().find(|trash_info| trash_info.path.to_str() == Some(name)).unwrap();
+        let size = disk_usage(&trash_info.trash_file_path())?;
+        total_size += size;
+
+        let cells = cells(name);
+        assert_eq!(cells[3], entry_type);
+        assert_eq!(cells[4], size.to_string());
+        assert_eq!(cells.get(5).map(String::as_str), item_count);
+    }
+
+    assert_eq!(cells("3 items"), vec!["3 items".to_string(), total_size.to_string()]);
+    assert!(trash_list_with_options(&options).is_ok());
+
+    remove_dir_all(&test_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+fn test_list_columns() {
+    assert_eq!(ListOptions::default().columns(), vec![ListColumn::Name, ListColumn::Location, ListColumn::DeletionDate, ListColumn::Type]);
+    assert_eq!(
+        ListOptions { full_path: true, sizes: true, ..ListOptions::default() }.columns(),
+        vec![ListColumn::OriginalPath, ListColumn::DeletionDate, ListColumn::Type, ListColumn::Size, ListColumn::Items]
+    );
+    assert_eq!(
+        ListOptions { columns: vec!["path".parse().unwrap(), "trash".parse().unwrap()], ..ListOptions::default() }.columns(),
+        vec![ListColumn::OriginalPath, ListColumn::TrashDir]
+    );
+    assert!("bogus".parse::<ListColumn>().is_err());
+}
+
+#[test]
+#[serial]
+fn test_trash_contents_table_shows_original_location() {
+    let table = trash_contents_table(&[test_file_trash_entry(true)], &ListOptions::default());
+    assert!(table.contains("Original Location"));
+    assert!(table.contains("/tmp"));
+    assert!(table.contains("│ 1 item "));
+
+    let table = trash_contents_table(&[test_file_trash_entry(true)], &ListOptions { full_path: true, ..ListOptions::default() });
+    assert!(table.contains("/tmp/test.txt"));
+}
+
+#[test]
+fn test_machine_readable_formats() -> Result<(), Error> {
+    let trash_contents = [test_file_trash_entry(true)];
+    let format = |format| format_trash_contents(&trash_contents, &ListOptions { format, ..ListOptions::default() });
+
+    let json: serde_json::Value = serde_json::from_str(&format(ListFormat::Json)?)?;
+    assert_eq!(json[0]["name"], "test.txt");
+    assert_eq!(json[0]["original_path"], "/tmp/test.txt");
+    assert_eq!(json[0]["deletion_date"], "2004-08-31T22:32:08");
+    assert!(json[0]["size"].is_null());
+
+    assert_eq!(format(ListFormat::Ndjson)?.lines().count(), 1);
+
+    let csv = format(ListFormat::Csv)?;
+    assert_eq!(csv.lines().next(), Some("name,original_path,deletion_date,trash_dir,size,type"));
+    assert!(csv.lines().nth(1).unwrap().starts_with("test.txt,/tmp/test.txt,2004-08-31T22:32:08,"));
+
+    assert!(format(ListFormat::Tsv)?.starts_with("name\toriginal_path\t"));
+
+    // an empty trash still has the header row
+    let empty = |format| format_trash_contents(&[], &ListOptions { format, ..ListOptions::default() });
+    assert_eq!(empty(ListFormat::Csv)?, "name,original_path,deletion_date,trash_dir,size,type\n");
+    assert_eq!(empty(ListFormat::Tsv)?, "name\toriginal_path\tdeletion_date\ttrash_dir\tsize\ttype\n");
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_recursive_list_formats() {
+    setup_xdg_data_home();
+
+    assert!(trash_list_with_options(&ListOptions { recursive: true, format: ListFormat::Json, ..ListOptions::default() }).is_ok());
+    assert!(trash_list_with_options(&ListOptions { recursive: true, format: ListFormat::Csv, ..ListOptions::default() }).is_err());
+
+    remove_trash_file_hierarchy();
+}
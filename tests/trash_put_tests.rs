@@ -1,10 +1,14 @@
 use std::{
-    fs::exists, 
+    env::{set_var, temp_dir},
+    ffi::OsStr,
+    fs::{create_dir_all, exists, read_dir, read_link, read_to_string, remove_dir_all, remove_file, symlink_metadata, write}, 
     io::{
         Error,
         ErrorKind, 
         Result
     },
+    os::unix::{ffi::OsStrExt, fs::symlink},
+    path::Path,
     process::{
         Command, 
         Output
@@ -12,16 +16,22 @@ use std::{
 };
 
 use common::{
-    remove_trash_file_hierarchy, 
+    remove_trash_file_hierarchy,
+    setup_test_dir,
     setup_xdg_data_home
 };
 use oscar::{
-    actions::trash_put::trash_put, 
+    actions::{trash_list::trash_list, trash_put::{check_rm_compatible, trash_info_original_path, trash_put, trash_put_many, trash_put_with_mover, trash_put_with_options, PutOptions}, trash_remove::trash_remove, trash_restore::trash_restore}, 
     common::{
+        freedesktop_home_trash_dir,
         freedesktop_home_trash_files_dir, 
-        freedesktop_home_trash_info_dir
+        freedesktop_home_trash_info_dir,
+        freedesktop_topdir_user_trash_dir,
+        get_home_trash_contents,
+        get_trash_contents
     }
 };
+use oscar::file_move::{move_by_copying_with, IncompleteMoveError};
 use serial_test::serial;
 
 mod common;
@@ -39,7 +49,7 @@ fn test_basic_trash_put() -> Result<()> {
     
     match create_test_file() {
         Ok(_) => {
-            match trash_put(&String::from("/tmp/test.txt")) {
+            match trash_put("/tmp/test.txt") {
                 Ok(_) => {
                     match freedesktop_home_trash_files_dir() {
                         Some(trash_files_dir) => {
@@ -60,7 +70,7 @@ fn test_basic_trash_put() -> Result<()> {
                                                     Err(err) => Err(err)
                                                 }
                                             },
-                                            None => Err(Error::new(ErrorKind::Other, "Could not determine trash path"))
+                                            None => Err(Error::other("Could not determine trash path"))
                                         }
                                     } else {
                                         Err(Error::new(ErrorKind::NotFound, "trashed file not in expected path"))
@@ -69,7 +79,7 @@ fn test_basic_trash_put() -> Result<()> {
                                 Err(err) => Err(err)
                             }
                         },
-                        None => Err(Error::new(ErrorKind::Other, "Could not determine trash path"))
+                        None => Err(Error::other("Could not determine trash path"))
                     }
                 },
                 Err(err) => Err(err)
@@ -84,8 +94,262 @@ fn test_basic_trash_put() -> Result<()> {
 fn test_trash_put_nonexistent_file() {
     setup_xdg_data_home();
 
-    match trash_put(&String::from("/tmp/does-not-exist.txt")) {
-        Ok(_) => assert!(false),
-        Err(_) => assert!(true)
-    }
-}
\ No newline at end of file
+    assert!(trash_put("/tmp/does-not-exist.txt").is_err());
+}
+
+#[test]
+#[serial]
+fn test_trash_put_same_name_does_not_clobber() -> Result<()> {
+    setup_xdg_data_home();
+
+    let (first_dir, second_dir) = ("/tmp/oscar-put-first", "/tmp/oscar-put-second");
+    create_dir_all(first_dir)?;
+    create_dir_all(second_dir)?;
+    write(format!("{first_dir}/notes.txt"), "first")?;
+    write(format!("{second_dir}/notes.txt"), "second")?;
+
+    trash_put(format!("{first_dir}/notes.txt"))?;
+    trash_put(format!("{second_dir}/notes.txt"))?;
+
+    let (trash_files_dir, trash_info_dir) = (
+        freedesktop_home_trash_files_dir().unwrap(),
+        freedesktop_home_trash_info_dir().unwrap()
+    );
+
+    assert_eq!(read_to_string(trash_files_dir.join("notes.txt"))?, "first");
+    assert_eq!(read_to_string(trash_files_dir.join("notes.2.txt"))?, "second");
+    assert!(exists(trash_info_dir.join("notes.txt.trashinfo"))?);
+    assert!(exists(trash_info_dir.join("notes.2.txt.trashinfo"))?);
+
+    let mut trash_contents = get_home_trash_contents()?;
+    trash_contents.sort_by(|a, b| a.path.cmp(&b.path));
+    assert_eq!(trash_contents[0].path, "notes.2.txt");
+    assert_eq!(trash_contents[0].full_path, format!("{second_dir}/notes.txt"));
+    assert_eq!(trash_contents[1].path, "notes.txt");
+    assert_eq!(trash_contents[1].full_path, format!("{first_dir}/notes.txt"));
+
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_trash_put_does_not_clobber_dangling_symlink_payload() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir("oscar-put-dangling-payload");
+    write(test_dir.join("notes.txt"), "notes")?;
+
+    // a leftover payload without a trashinfo file, which only exists when symlinks are not followed
+    let trash_files_dir = freedesktop_home_trash_files_dir().unwrap();
+    create_dir_all(&trash_files_dir)?;
+    symlink("nowhere", trash_files_dir.join("notes.txt"))?;
+
+    trash_put(test_dir.join("notes.txt"))?;
+
+    assert_eq!(read_link(trash_files_dir.join("notes.txt"))?, Path::new("nowhere"));
+    assert_eq!(read_to_string(trash_files_dir.join("notes.2.txt"))?, "notes");
+
+    remove_dir_all(&test_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_trash_put_non_utf8_file_name() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    // "café.txt" encoded as Latin-1
+    let file_name = OsStr::from_bytes(b"caf\xe9.txt");
+    let test_file = temp_dir().join(file_name);
+    write(&test_file, "latin-1")?;
+
+    trash_put(&test_file)?;
+
+    let trash_contents = get_home_trash_contents()?;
+    assert_eq!(trash_contents.len(), 1);
+    assert_eq!(trash_contents[0].path, file_name);
+    assert_eq!(trash_contents[0].full_path, test_file);
+    trash_list(false)?;
+
+    trash_restore(&trash_contents[0], false)?;
+    assert_eq!(read_to_string(&test_file)?, "latin-1");
+
+    remove_file(&test_file)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_trash_put_symlink_trashes_link_not_target() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir("oscar-put-symlink");
+    write(test_dir.join("target.txt"), "target")?;
+    symlink(test_dir.join("target.txt"), test_dir.join("link"))?;
+
+    trash_put(test_dir.join("link"))?;
+
+    assert!(!exists(test_dir.join("link"))?);
+    assert_eq!(read_to_string(test_dir.join("target.txt"))?, "target");
+
+    let trash_contents = get_home_trash_contents()?;
+    assert_eq!(trash_contents.len(), 1);
+    assert_eq!(trash_contents[0].path, "link");
+    assert_eq!(trash_contents[0].full_path, test_dir.join("link"));
+    assert!(symlink_metadata(trash_contents[0].trash_file_path())?.is_symlink());
+
+    trash_restore(&trash_contents[0], false)?;
+    assert_eq!(read_link(test_dir.join("link"))?, test_dir.join("target.txt"));
+
+    remove_dir_all(&test_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_trash_put_dangling_symlink() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir("oscar-put-dangling-symlink");
+    symlink(test_dir.join("does-not-exist"), test_dir.join("dangling"))?;
+
+    trash_put(test_dir.join("dangling"))?;
+
+    assert!(symlink_metadata(test_dir.join("dangling")).is_err());
+    let trash_contents = get_home_trash_contents()?;
+    assert_eq!(trash_contents.len(), 1);
+    assert_eq!(trash_contents[0].full_path, test_dir.join("dangling"));
+
+    trash_remove(&trash_contents[0])?;
+    assert!(get_home_trash_contents()?.is_empty());
+
+    remove_dir_all(&test_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_trash_put_many_continues_past_failures() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir("oscar-put-many");
+    write(test_dir.join("first.log"), "")?;
+    write(test_dir.join("third.log"), "")?;
+
+    let paths = [test_dir.join("first.log"), test_dir.join("second.log"), test_dir.join("third.log")];
+    let results = trash_put_many(&paths, &PutOptions::default());
+
+    assert_eq!(results.iter().map(|(path, _)| path.clone()).collect::<Vec<_>>(), paths.to_vec());
+    assert!(results[0].1.is_ok());
+    assert_eq!(results[1].1.as_ref().unwrap_err().kind(), ErrorKind::NotFound);
+    assert!(results[2].1.is_ok());
+    assert_eq!(get_home_trash_contents()?.len(), 2);
+
+    remove_dir_all(&test_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_check_rm_compatible_directories() -> Result<()> {
+    let test_dir = setup_test_dir("oscar-put-rm-compatible");
+    create_dir_all(test_dir.join("empty"))?;
+    create_dir_all(test_dir.join("full"))?;
+    write(test_dir.join("full").join("file.txt"), "")?;
+
+    assert!(check_rm_compatible(&test_dir.join("full").join("file.txt"), false, false).is_ok());
+    assert_eq!(check_rm_compatible(&test_dir.join("empty"), false, false).unwrap_err().kind(), ErrorKind::IsADirectory);
+    assert!(check_rm_compatible(&test_dir.join("empty"), false, true).is_ok());
+    assert_eq!(check_rm_compatible(&test_dir.join("full"), false, true).unwrap_err().kind(), ErrorKind::DirectoryNotEmpty);
+    assert!(check_rm_compatible(&test_dir.join("full"), true, false).is_ok());
+    assert_eq!(check_rm_compatible(&test_dir.join("missing"), true, false).unwrap_err().kind(), ErrorKind::NotFound);
+
+    remove_dir_all(&test_dir)
+}
+
+#[test]
+#[serial]
+fn test_failed_trash_put_rolls_back_trash_info() -> Result<()> {
+    // a directory cannot be moved into its own trash, so the move fails after the trashinfo file is written
+    let test_dir = setup_test_dir("oscar-put-rollback");
+    set_var("XDG_DATA_HOME", test_dir.join("xdg"));
+
+    let options = PutOptions { allow_protected: true, ..PutOptions::default() };
+    let result = trash_put_with_options(&test_dir, &options);
+    let info_dir = freedesktop_home_trash_info_dir().unwrap();
+
+    setup_xdg_data_home();
+    assert!(result.is_err());
+    assert!(exists(&test_dir)?);
+    assert_eq!(read_dir(info_dir)?.count(), 0);
+
+    remove_dir_all(&test_dir)
+}
+
+#[test]
+#[serial]
+fn test_trash_put_keeps_trash_info_when_source_is_not_fully_removed() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir("oscar-put-incomplete");
+    create_dir_all(test_dir.join("report").join("nested"))?;
+    write(test_dir.join("report").join("nested").join("data.txt"), "data")?;
+
+    // copy into the trash like a put across file systems does, but fail to remove the source afterwards
+    let result = trash_put_with_mover(test_dir.join("report"), &PutOptions::default(), |from, to| {
+        move_by_copying_with(from, to, |_| Err(Error::from(ErrorKind::PermissionDenied)))
+    });
+
+    // the source removal failure is reported, but the complete copy in the trash stays restorable
+    assert!(IncompleteMoveError::from_io_error(&result.unwrap_err()).is_some());
+    let trash_contents = get_home_trash_contents()?;
+    assert_eq!(trash_contents.len(), 1);
+    assert_eq!(trash_contents[0].full_path, test_dir.join("report"));
+    assert_eq!(read_to_string(trash_contents[0].trash_file_path().join("nested").join("data.txt"))?, "data");
+
+    remove_dir_all(&test_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_trash_put_writes_relative_path_in_topdir_trash() -> Result<()> {
+    setup_xdg_data_home();
+
+    let top_dir = setup_test_dir("oscar-put-top-dir");
+    let topdir_trash_dir = freedesktop_topdir_user_trash_dir(&top_dir);
+    assert_eq!(trash_info_original_path(&topdir_trash_dir, &top_dir.join("docs/report.txt")), Path::new("docs/report.txt"));
+    assert_eq!(
+        trash_info_original_path(&freedesktop_home_trash_dir().unwrap(), &top_dir.join("docs/report.txt")),
+        top_dir.join("docs/report.txt")
+    );
+
+    // end to end, through the trash of a top directory of our own
+    create_dir_all(top_dir.join("docs"))?;
+    write(top_dir.join("docs").join("report.txt"), "report")?;
+
+    trash_put_with_options(top_dir.join("docs").join("report.txt"), &PutOptions { top_dir: Some(top_dir.clone()), ..PutOptions::default() })?;
+
+    let trash_info = read_to_string(topdir_trash_dir.join("info").join("report.txt.trashinfo"))?;
+    assert!(trash_info.contains("\nPath=docs/report.txt\n"));
+    let trash_contents = get_trash_contents(&topdir_trash_dir)?;
+    assert_eq!(trash_contents.len(), 1);
+    assert_eq!(trash_contents[0].full_path, top_dir.join("docs").join("report.txt"));
+    assert_eq!(read_to_string(trash_contents[0].trash_file_path())?, "report");
+
+    remove_dir_all(&top_dir)?;
+    Ok(())
+}
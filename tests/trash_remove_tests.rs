@@ -2,7 +2,6 @@ use std::{
     fs::exists, 
     io::{
         Error, 
-        ErrorKind, 
         Result
     },
     path::Path, 
@@ -12,7 +11,7 @@ use std::{
 use common::{setup_xdg_data_home, test_file_trash_entry};
 use configparser::ini::Ini;
 use oscar::{
-    actions::trash_remove::trash_remove, 
+    actions::trash_remove::{trash_remove, trash_remove_many}, 
     common::{
         freedesktop_home_trash_files_dir, 
         freedesktop_home_trash_info_dir, 
@@ -45,13 +44,13 @@ fn create_home_trash_hierarchy() -> Result<()> {
                         Err(error) => Err(error)
                     }
                 } else {
-                    Err(Error::new(ErrorKind::Other, "Failed to compute trash directory"))
+                    Err(Error::other("Failed to compute trash directory"))
                 }
             },
             Err(error) => Err(error)
         }
     } else {
-        Err(Error::new(ErrorKind::Other, "Failed to compute trash directory"))
+        Err(Error::other("Failed to compute trash directory"))
     }
 }
 
@@ -71,32 +70,32 @@ fn setup_home_trash(is_file: bool) -> Result<()> {
 
             let create_trash_test_file = if is_file {
                 Command::new("touch")
-                    .arg(&trash_files_dir.join(&test_file))
+                    .arg(trash_files_dir.join(&test_file))
                     .output()
             } else {
                 Command::new("mkdir")
                     .arg("-p")
-                    .arg(&trash_files_dir.join(&test_file))
+                    .arg(trash_files_dir.join(&test_file))
                     .output()
             };
-            if let Ok(_) = create_trash_test_file {
+            if create_trash_test_file.is_ok() {
                 let mut trashinfo = Ini::new();
-                if let Ok(_) = trashinfo.read(format!("[Trash Info]
+                if trashinfo.read(format!("[Trash Info]
                     Path=/tmp/{test_file}
                     DeletionDate=2004-08-31T22:32:08"
-                )) {
-                    if let Ok(_) = trashinfo.write(
-                        &trash_info_dir.join(with_trashinfo_extension(&Path::new(&test_file).to_path_buf()))
-                    ) {
+                )).is_ok() {
+                    if trashinfo.write(
+                        trash_info_dir.join(with_trashinfo_extension(Path::new(&test_file)))
+                    ).is_ok() {
                         Ok(())
                     } else {
-                        Err(Error::new(ErrorKind::Other, "Failed to create trashinfo"))
+                        Err(Error::other("Failed to create trashinfo"))
                     }
                 } else {
-                    Err(Error::new(ErrorKind::Other, "Failed to parse"))
+                    Err(Error::other("Failed to parse"))
                 }
             } else {
-                Err(Error::new(ErrorKind::Other, "Failed to create test file"))
+                Err(Error::other("Failed to create test file"))
             }
         },
         Err(error) => Err(error)
@@ -119,11 +118,11 @@ fn test_trash_rm() -> Result<()> {
 
             match trash_remove(&trash_entry_to_rm) {
                 Ok(_) => {
-                    match exists(&trash_info_dir.join(with_trashinfo_extension(&Path::new(&trash_entry_to_rm.path).to_path_buf()))) {
-                        Ok(true) => Err(Error::new(ErrorKind::Other, "trash info not deleted")),
-                        Ok(false) => match exists(&trash_files_dir.join(&trash_entry_to_rm.path)) {
+                    match exists(trash_info_dir.join(with_trashinfo_extension(Path::new(&trash_entry_to_rm.path)))) {
+                        Ok(true) => Err(Error::other("trash info not deleted")),
+                        Ok(false) => match exists(trash_files_dir.join(&trash_entry_to_rm.path)) {
                             Ok(false) => Ok(()),
-                            Ok(true) => Err(Error::new(ErrorKind::Other, "trash file not deleted")),
+                            Ok(true) => Err(Error::other("trash file not deleted")),
                             Err(err) => Err(err)
                         },
                         Err(err) => Err(err)
@@ -152,11 +151,11 @@ fn test_trash_rmdir() -> Result<()> {
 
             match trash_remove(&trash_entry_to_rm) {
                 Ok(_) => {
-                    match exists(&trash_info_dir.join(with_trashinfo_extension(&Path::new(&trash_entry_to_rm.path).to_path_buf()))) {
-                        Ok(true) => Err(Error::new(ErrorKind::Other, "trash info not deleted")),
-                        Ok(false) => match exists(&trash_files_dir.join(&trash_entry_to_rm.path)) {
+                    match exists(trash_info_dir.join(with_trashinfo_extension(Path::new(&trash_entry_to_rm.path)))) {
+                        Ok(true) => Err(Error::other("trash info not deleted")),
+                        Ok(false) => match exists(trash_files_dir.join(&trash_entry_to_rm.path)) {
                             Ok(false) => Ok(()),
-                            Ok(true) => Err(Error::new(ErrorKind::Other, "trash file not deleted")),
+                            Ok(true) => Err(Error::other("trash file not deleted")),
                             Err(err) => Err(err)
                         },
                         Err(err) => Err(err)
@@ -167,4 +166,24 @@ fn test_trash_rmdir() -> Result<()> {
         },
         Err(error) => Err(error)
     }
-}
\ No newline at end of file
+}
+
+#[test]
+#[serial]
+fn test_trash_rm_many_continues_past_failures() -> Result<()> {
+    const IS_TRASH_ENTRY_FILE: bool = true;
+
+    setup_home_trash(IS_TRASH_ENTRY_FILE)?;
+
+    // the directory entry was never created, removing it fails
+    let trash_entries = [test_file_trash_entry(false), test_file_trash_entry(IS_TRASH_ENTRY_FILE)];
+    let _ = std::fs::remove_dir_all(freedesktop_home_trash_files_dir().unwrap().join(&trash_entries[0].path));
+
+    let results = trash_remove_many(&trash_entries);
+    assert_eq!(results.len(), 2);
+    assert!(results[0].1.is_err());
+    assert!(results[1].1.is_ok());
+    assert!(!exists(freedesktop_home_trash_files_dir().unwrap().join(&trash_entries[1].path))?);
+
+    Ok(())
+}
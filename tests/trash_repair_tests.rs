@@ -0,0 +1,67 @@
+use std::{
+    fs::{create_dir_all, exists, write, File, FileTimes},
+    io::Result,
+    path::Path,
+    time::{Duration, SystemTime}
+};
+
+use common::{
+    remove_trash_file_hierarchy, 
+    setup_xdg_data_home
+};
+use oscar::{
+    common::{create_home_trash_files_dir_if_not_exists, create_home_trash_info_dir_if_not_exists, freedesktop_home_trash_dir, freedesktop_home_trash_files_dir, freedesktop_home_trash_info_dir},
+    trash_repair::{repair_trash_dir, IncompleteTrashEntry}
+};
+use serial_test::serial;
+
+mod common;
+
+fn write_trash_info(info_dir: &Path, name: &str, age: Duration) -> Result<()> {
+    let trash_info_path = info_dir.join(format!("{name}.trashinfo"));
+    write(&trash_info_path, format!("[Trash Info]\nPath=/tmp/{name}\nDeletionDate=2004-08-31T22:32:08\n"))?;
+
+    File::options().write(true).open(&trash_info_path)?
+        .set_times(FileTimes::new().set_modified(SystemTime::now() - age))
+}
+
+#[test]
+#[serial]
+fn test_repair_trash_dir() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+    create_home_trash_files_dir_if_not_exists()?;
+    create_home_trash_info_dir_if_not_exists()?;
+
+    let files_dir = freedesktop_home_trash_files_dir().unwrap();
+    let info_dir = freedesktop_home_trash_info_dir().unwrap();
+    let an_hour = Duration::from_secs(3600);
+
+    // a complete entry, an abandoned trashinfo, one that may still be in progress and a partial copy of a dead process
+    write_trash_info(&info_dir, "complete.txt", an_hour)?;
+    write(files_dir.join("complete.txt"), "")?;
+    write_trash_info(&info_dir, "abandoned.txt", an_hour)?;
+    write_trash_info(&info_dir, "in-progress.txt", Duration::ZERO)?;
+    create_dir_all(files_dir.join(format!(".dir.oscar-partial-{}", u32::MAX)))?;
+
+    // a file that only looks like a partial copy, but was trashed on purpose
+    let lookalike = format!(".notes.oscar-partial-{}", u32::MAX);
+    write_trash_info(&info_dir, &lookalike, an_hour)?;
+    write(files_dir.join(&lookalike), "")?;
+
+    let mut repaired = repair_trash_dir(&freedesktop_home_trash_dir().unwrap())?;
+    repaired.sort_by_key(|entry| format!("{entry:?}"));
+
+    assert_eq!(repaired, vec![
+        IncompleteTrashEntry::OrphanedTrashInfo(info_dir.join("abandoned.txt.trashinfo")),
+        IncompleteTrashEntry::PartialCopy(files_dir.join(format!(".dir.oscar-partial-{}", u32::MAX))),
+    ]);
+    assert!(exists(info_dir.join("complete.txt.trashinfo"))?);
+    assert!(exists(files_dir.join("complete.txt"))?);
+    assert!(exists(info_dir.join("in-progress.txt.trashinfo"))?);
+    assert!(!exists(info_dir.join("abandoned.txt.trashinfo"))?);
+    assert!(exists(files_dir.join(&lookalike))?);
+
+    remove_trash_file_hierarchy();
+    Ok(())
+}
@@ -1,21 +1,24 @@
 use std::{
-    fs::exists, 
+    env::temp_dir,
+    fs::{create_dir_all, exists, read_to_string, remove_dir_all, write}, 
     io::{
         Error, 
-        ErrorKind, 
+        ErrorKind,
         Result
     },
-    path::Path, 
+    path::{Path, PathBuf}, 
     process::Command
 };
 
-use common::{remove_trash_file_hierarchy, setup_xdg_data_home, test_file, test_file_trash_entry};
+use common::{remove_trash_file_hierarchy, setup_test_dir, setup_xdg_data_home, test_file, test_file_trash_entry, test_trash_entry};
 use configparser::ini::Ini;
 use oscar::{
-    actions::trash_restore::trash_restore, 
+    actions::{trash_put::trash_put, trash_restore::{restore_candidates, restore_destination, trash_restore, trash_restore_to, ConflictStrategy, MissingParentError, RestoreOptions, RestoreOutcome}}, 
     common::{
-        create_home_trash_dir_if_not_exists, freedesktop_home_trash_files_dir, freedesktop_home_trash_info_dir, with_trashinfo_extension
-    }
+        create_home_trash_dir_if_not_exists, freedesktop_home_trash_dir, freedesktop_home_trash_files_dir, freedesktop_home_trash_info_dir, 
+        freedesktop_topdir_user_trash_dir, get_trash_contents, with_trashinfo_extension
+    },
+    trash_info::TrashInfo
 };
 use serial_test::serial;
 
@@ -36,7 +39,7 @@ fn create_home_trash() -> Result<()> {
             );
 
             let create_test_file_cmd = Command::new("touch")
-                .arg(&home_trash_files_dir.join(&test_file))
+                .arg(home_trash_files_dir.join(&test_file))
                 .output();
 
             match create_test_file_cmd {
@@ -48,13 +51,13 @@ fn create_home_trash() -> Result<()> {
                     )) {
                         Ok(_) => {
                             match trashinfo.write(
-                                &home_trash_info_dir.join(with_trashinfo_extension(&Path::new(&test_file).to_path_buf()))
+                                home_trash_info_dir.join(with_trashinfo_extension(Path::new(&test_file)))
                             ) {
                                 Ok(_) => Ok(()),
                                 Err(err) => Err(err)
                             }
                         },
-                        Err(err) => Err(Error::new(ErrorKind::Other, err))
+                        Err(err) => Err(Error::other(err))
                     }
                 },
                 Err(err) => Err(err)
@@ -95,7 +98,7 @@ fn test_trash_restore_overwrite_file_in_original_path() -> Result<()> {
     let test_trash_entry = test_file_trash_entry(true);
 
     let create_file_in_original_dir_cmd = Command::new("touch")
-        .arg(format!("/tmp/{}", &test_trash_entry.path))
+        .arg(&test_trash_entry.full_path)
         .output();
 
     match create_file_in_original_dir_cmd {
@@ -127,14 +130,14 @@ fn test_trash_restore_no_overwrite_file_in_original_path() -> Result<()> {
     let test_trash_entry = test_file_trash_entry(true);
 
     let create_file_in_original_dir_cmd = Command::new("touch")
-        .arg(format!("/tmp/{}", &test_trash_entry.path))
+        .arg(&test_trash_entry.full_path)
         .output();
 
     match create_file_in_original_dir_cmd {
         Ok(_) => {
             match create_home_trash() {
                 Ok(_) => match trash_restore(&test_trash_entry, false) {
-                    Ok(_) => Err(Error::new(ErrorKind::Other, "Trash restore was not supposed to overwrite")),
+                    Ok(_) => Err(Error::other("Trash restore was not supposed to overwrite")),
                     Err(_) => Ok(())
                 },
                 Err(err) => Err(err)
@@ -142,4 +145,206 @@ fn test_trash_restore_no_overwrite_file_in_original_path() -> Result<()> {
         },
         Err(err) => Err(err)
     }
-}
\ No newline at end of file
+}
+
+#[test]
+#[serial]
+fn test_trash_restore_relative_path_from_topdir_trash() -> Result<()> {
+    setup_xdg_data_home();
+
+    let top_dir = temp_dir().join("oscar-restore-top-dir");
+    let topdir_trash_dir = freedesktop_topdir_user_trash_dir(&top_dir);
+    create_dir_all(top_dir.join("docs"))?;
+    create_dir_all(topdir_trash_dir.join("files"))?;
+    create_dir_all(topdir_trash_dir.join("info"))?;
+    write(topdir_trash_dir.join("files").join("report.txt"), "report")?;
+    write(
+        with_trashinfo_extension(&topdir_trash_dir.join("info").join("report.txt")),
+        "[Trash Info]\nPath=docs/report.txt\nDeletionDate=2004-08-31T22:32:08\n"
+    )?;
+
+    let trash_contents = get_trash_contents(&topdir_trash_dir)?;
+    assert_eq!(trash_contents.len(), 1);
+    assert_eq!(trash_contents[0].full_path, top_dir.join("docs/report.txt"));
+
+    trash_restore(&trash_contents[0], false)?;
+    assert_eq!(read_to_string(top_dir.join("docs/report.txt"))?, "report");
+
+    remove_dir_all(&top_dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_restore_candidates_are_scoped_to_directory() {
+    let trash_contents = || vec![
+        test_trash_entry("/home/user/project/old.txt", 2),
+        test_trash_entry("/home/user/other.txt", 0),
+        test_trash_entry("/home/user/project/src/new.rs", 1),
+        test_trash_entry("/home/user/project", 3),
+    ];
+    let full_paths = |candidates: Vec<TrashInfo>| candidates.into_iter().map(|t| t.full_path).collect::<Vec<_>>();
+
+    assert_eq!(
+        full_paths(restore_candidates(trash_contents(), Some(Path::new("/home/user/project")))),
+        [PathBuf::from("/home/user/project/src/new.rs"), PathBuf::from("/home/user/project/old.txt")]
+    );
+    assert_eq!(
+        full_paths(restore_candidates(trash_contents(), None)),
+        [PathBuf::from("/home/user/other.txt"), PathBuf::from("/home/user/project/src/new.rs"), PathBuf::from("/home/user/project/old.txt"), PathBuf::from("/home/user/project")]
+    );
+}
+
+#[test]
+#[serial]
+fn test_trash_restore_to() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir("oscar-restore-to");
+    create_dir_all(test_dir.join("elsewhere"))?;
+    write(test_dir.join("notes.txt"), "notes")?;
+    write(test_dir.join("todo.txt"), "todo")?;
+    trash_put(test_dir.join("notes.txt"))?;
+    trash_put(test_dir.join("todo.txt"))?;
+
+    let mut trash_contents = get_trash_contents(&freedesktop_home_trash_dir().unwrap())?;
+    trash_contents.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // into an existing directory
+    let destination = restore_destination(&trash_contents[0], &test_dir.join("elsewhere"));
+    assert_eq!(destination, test_dir.join("elsewhere").join("notes.txt"));
+    trash_restore_to(&trash_contents[0], &destination, &RestoreOptions::default())?;
+    assert_eq!(read_to_string(&destination)?, "notes");
+    assert!(!exists(test_dir.join("notes.txt"))?);
+
+    // to a file path, under another name
+    let destination = restore_destination(&trash_contents[1], &test_dir.join("elsewhere").join("restored-todo.txt"));
+    assert_eq!(destination, test_dir.join("elsewhere").join("restored-todo.txt"));
+    trash_restore_to(&trash_contents[1], &destination, &RestoreOptions::default())?;
+    assert_eq!(read_to_string(&destination)?, "todo");
+    assert!(get_trash_contents(&freedesktop_home_trash_dir().unwrap())?.is_empty());
+
+    remove_dir_all(&test_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_trash_restore_missing_parent_dirs() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir("oscar-restore-parents");
+    create_dir_all(test_dir.join("project").join("src"))?;
+    write(test_dir.join("project").join("src").join("main.rs"), "fn main() {}")?;
+
+    // trash a file, then its parent's parent
+    trash_put(test_dir.join("project").join("src").join("main.rs"))?;
+    trash_put(test_dir.join("project"))?;
+
+    let trash_entry = get_trash_contents(&freedesktop_home_trash_dir().unwrap())?
+        .into_iter()
+        .find(|trash_entry| trash_entry.path == "main.rs")
+        .unwrap();
+
+    let error = trash_restore_to(&trash_entry, &trash_entry.full_path, &RestoreOptions::default()).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    assert_eq!(
+        MissingParentError::from_io_error(&error).unwrap().missing_dirs,
+        [test_dir.join("project"), test_dir.join("project").join("src")]
+    );
+
+    let report = trash_restore_to(&trash_entry, &trash_entry.full_path, &RestoreOptions { create_parents: true, ..RestoreOptions::default() })?;
+    assert_eq!(report.created_dirs, [test_dir.join("project"), test_dir.join("project").join("src")]);
+    assert_eq!(read_to_string(test_dir.join("project").join("src").join("main.rs"))?, "fn main() {}");
+
+    remove_dir_all(&test_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_trash_restore_conflict_strategies() -> Result<()> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir("oscar-restore-conflicts");
+    create_dir_all(test_dir.join("dir").join("sub"))?;
+    write(test_dir.join("notes.txt"), "trashed")?;
+    write(test_dir.join("dir").join("same.txt"), "trashed")?;
+    write(test_dir.join("dir").join("sub").join("only-trashed.txt"), "trashed")?;
+    trash_put(test_dir.join("notes.txt"))?;
+    trash_put(test_dir.join("dir"))?;
+
+    create_dir_all(test_dir.join("dir").join("sub"))?;
+    write(test_dir.join("notes.txt"), "current")?;
+    write(test_dir.join("dir").join("same.txt"), "current")?;
+    write(test_dir.join("dir").join("sub").join("only-current.txt"), "current")?;
+
+    let find_entry = |name: &str| get_trash_contents(&freedesktop_home_trash_dir().unwrap()).unwrap()
+        .into_iter()
+        .find(|trash_entry| trash_entry.path == name)
+        .unwrap();
+    let options = |on_conflict, on_file_conflict| RestoreOptions { on_conflict, on_file_conflict, ..RestoreOptions::default() };
+    assert_eq!(RestoreOptions::default().on_file_conflict, ConflictStrategy::Rename);
+
+    let notes = find_entry("notes.txt");
+    let report = trash_restore_to(&notes, &notes.full_path, &options(ConflictStrategy::Skip, ConflictStrategy::Fail))?;
+    assert_eq!(report.outcome, RestoreOutcome::Skipped);
+    assert!(exists(notes.trash_file_path())?);
+
+    assert_eq!(trash_restore_to(&notes, &notes.full_path, &RestoreOptions::default()).unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    let report = trash_restore_to(&notes, &notes.full_path, &options(ConflictStrategy::Rename, ConflictStrategy::Fail))?;
+    assert_eq!(report.outcome, RestoreOutcome::Renamed);
+    assert_eq!(report.destination, test_dir.join("notes (restored).txt"));
+    assert_eq!(read_to_string(test_dir.join("notes (restored).txt"))?, "trashed");
+
+    // merging fails before moving anything when conflicting files are not allowed
+    let dir = find_entry("dir");
+    let error = trash_restore_to(&dir, &dir.full_path, &options(ConflictStrategy::Merge, ConflictStrategy::Fail)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    assert!(exists(dir.trash_file_path().join("sub").join("only-trashed.txt"))?);
+    assert!(!exists(test_dir.join("dir").join("sub").join("only-trashed.txt"))?);
+
+    // merging with skipped files leaves them in the trash
+    let report = trash_restore_to(&dir, &dir.full_path, &options(ConflictStrategy::Merge, ConflictStrategy::Skip))?;
+    assert_eq!(report.outcome, RestoreOutcome::PartiallyMerged);
+    assert_eq!(read_to_string(test_dir.join("dir").join("same.txt"))?, "current");
+    assert!(exists(test_dir.join("dir").join("sub").join("only-trashed.txt"))?);
+    assert!(exists(test_dir.join("dir").join("sub").join("only-current.txt"))?);
+    assert!(exists(dir.trash_file_path().join("same.txt"))?);
+
+    // overwriting places the current file in the trash instead of deleting it
+    let report = trash_restore_to(&dir, &dir.full_path, &options(ConflictStrategy::Merge, ConflictStrategy::Overwrite))?;
+    assert_eq!(report.outcome, RestoreOutcome::Merged);
+    assert_eq!(read_to_string(test_dir.join("dir").join("same.txt"))?, "trashed");
+    assert!(!exists(dir.trash_info_path())?);
+    assert_eq!(read_to_string(find_entry("same.txt").trash_file_path())?, "current");
+
+    // overwriting a directory places the whole existing directory in the trash
+    create_dir_all(test_dir.join("tree"))?;
+    write(test_dir.join("tree").join("trashed.txt"), "trashed")?;
+    trash_put(test_dir.join("tree"))?;
+    create_dir_all(test_dir.join("tree"))?;
+    write(test_dir.join("tree").join("current.txt"), "current")?;
+
+    let tree = find_entry("tree");
+    let report = trash_restore_to(&tree, &tree.full_path, &options(ConflictStrategy::Overwrite, ConflictStrategy::Fail))?;
+    assert_eq!(report.outcome, RestoreOutcome::Overwritten);
+    assert_eq!(read_to_string(test_dir.join("tree").join("trashed.txt"))?, "trashed");
+    assert!(!exists(test_dir.join("tree").join("current.txt"))?);
+
+    let trashed_trees = get_trash_contents(&freedesktop_home_trash_dir().unwrap())?
+        .into_iter()
+        .filter(|trash_entry| trash_entry.full_path == test_dir.join("tree"))
+        .collect::<Vec<_>>();
+    assert_eq!(trashed_trees.len(), 1);
+    assert_eq!(read_to_string(trashed_trees[0].trash_file_path().join("current.txt"))?, "current");
+
+    remove_dir_all(&test_dir)?;
+    remove_trash_file_hierarchy();
+    Ok(())
+}
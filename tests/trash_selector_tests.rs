@@ -0,0 +1,57 @@
+use std::{ffi::OsStr, io::ErrorKind};
+
+use common::test_trash_entry;
+use oscar::{
+    trash_info::TrashInfo,
+    trash_selector::{lookup_trash_entries, select_trash_entries, AmbiguousSelectorError}
+};
+
+mod common;
+
+fn trash_entry(name: &str, full_path: &str) -> TrashInfo {
+    TrashInfo { path: name.into(), ..test_trash_entry(full_path, 0) }
+}
+
+fn trash_contents() -> Vec<TrashInfo> {
+    vec![
+        trash_entry("report.pdf", "/home/user/a/report.pdf"),
+        trash_entry("report.2.pdf", "/home/user/b/report.pdf"),
+        trash_entry("app.log", "/var/tmp/app.log"),
+        trash_entry("notes.txt", "/home/user/notes.txt"),
+    ]
+}
+
+fn selected_names(trash_contents: &[TrashInfo], selector: &str) -> Vec<String> {
+    select_trash_entries(trash_contents, OsStr::new(selector))
+        .iter()
+        .map(|trash_info| trash_info.path.to_string_lossy().into_owned())
+        .collect()
+}
+
+#[test]
+fn test_select_trash_entries() {
+    let trash_contents = trash_contents();
+
+    assert_eq!(selected_names(&trash_contents, "report.2.pdf"), ["report.2.pdf"]);
+    assert_eq!(selected_names(&trash_contents, "report.pdf"), ["report.pdf", "report.2.pdf"]);
+    assert_eq!(selected_names(&trash_contents, "/home/user/b/report.pdf"), ["report.2.pdf"]);
+    assert_eq!(selected_names(&trash_contents, "*.pdf"), ["report.pdf", "report.2.pdf"]);
+    assert_eq!(selected_names(&trash_contents, "/home/user/*"), ["notes.txt"]);
+    assert_eq!(selected_names(&trash_contents, "/home/user/*/report.pdf"), ["report.pdf", "report.2.pdf"]);
+    assert!(selected_names(&trash_contents, "missing.txt").is_empty());
+    assert!(selected_names(&trash_contents, "/home/user/missing.txt").is_empty());
+}
+
+#[test]
+fn test_lookup_trash_entries() {
+    let trash_contents = trash_contents();
+
+    assert_eq!(lookup_trash_entries(&trash_contents, OsStr::new("app.log"), false).unwrap(), vec![trash_contents[2].clone()]);
+    assert_eq!(lookup_trash_entries(&trash_contents, OsStr::new("missing.txt"), false).unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(lookup_trash_entries(&trash_contents, OsStr::new("*.pdf"), true).unwrap().len(), 2);
+
+    let error = lookup_trash_entries(&trash_contents, OsStr::new("*.pdf"), false).unwrap_err();
+    let ambiguous_selector_error = AmbiguousSelectorError::from_io_error(&error).unwrap();
+    assert_eq!(ambiguous_selector_error.candidates, trash_contents[..2].to_vec());
+    assert!(error.to_string().contains("/home/user/b/report.pdf"));
+}
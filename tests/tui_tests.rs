@@ -0,0 +1,107 @@
+mod common;
+
+use std::{
+    fs::{create_dir_all, read_to_string, remove_dir_all, write},
+    io::Result,
+    path::PathBuf
+};
+
+use common::{remove_trash_file_hierarchy, setup_test_dir, setup_xdg_data_home};
+use oscar::{actions::trash_put::trash_put, trash_filter::TrashFilter, tui::TrashBrowser};
+use ratatui::{backend::TestBackend, crossterm::event::{KeyCode, KeyEvent}, Terminal};
+use serial_test::serial;
+
+fn press(browser: &mut TrashBrowser, keys: &str) {
+    for c in keys.chars() {
+        browser.handle_key(KeyEvent::from(KeyCode::Char(c)));
+    }
+}
+
+fn render(browser: &mut TrashBrowser) -> Result<String> {
+    let mut terminal = Terminal::new(TestBackend::new(120, 20))?;
+    terminal.draw(|frame| browser.draw(frame))?;
+
+    let buffer = terminal.backend().buffer();
+    let lines: Vec<String> = (0..buffer.area.height).map(|y| {
+        (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect()
+    }).collect();
+
+    Ok(lines.join("\n"))
+}
+
+/// Trashes `name/dir`, holding a nested directory, and `name/notes.txt`
+fn setup_trashed_entries(name: &str) -> Result<PathBuf> {
+    setup_xdg_data_home();
+    remove_trash_file_hierarchy();
+
+    let test_dir = setup_test_dir(name);
+    create_dir_all(test_dir.join("dir").join("nested"))?;
+    write(test_dir.join("notes.txt"), "remember the milk")?;
+    write(test_dir.join("dir").join("nested").join("deep.txt"), "")?;
+
+    trash_put(test_dir.join("dir"))?;
+    trash_put(test_dir.join("notes.txt"))?;
+
+    Ok(test_dir)
+}
+
+#[test]
+#[serial]
+fn test_browser_lists_and_previews_entries() -> Result<()> {
+    let test_dir = setup_trashed_entries("oscar-tui-preview")?;
+    let mut browser = TrashBrowser::new(TrashFilter::default())?;
+
+    let screen = render(&mut browser)?;
+    assert!(screen.contains("notes.txt"));
+    assert!(screen.contains("2 items of 2"));
+
+    press(&mut browser, "/ntes");
+    assert_eq!(browser.shown_entries().len(), 1);
+    let screen = render(&mut browser)?;
+    assert!(screen.contains("Filter: ntes"));
+    assert!(screen.contains("remember the milk"));
+
+    browser.handle_key(KeyEvent::from(KeyCode::Esc));
+    assert_eq!(browser.shown_entries().len(), 2);
+
+    press(&mut browser, "/preview/dir");
+    browser.handle_key(KeyEvent::from(KeyCode::Enter));
+    let screen = render(&mut browser)?;
+    assert!(screen.contains("└── nested"));
+    assert!(screen.contains("deep.txt"));
+
+    press(&mut browser, "q");
+    assert!(browser.should_quit());
+
+    remove_dir_all(test_dir)?;
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_browser_actions_ask_for_confirmation() -> Result<()> {
+    let test_dir = setup_trashed_entries("oscar-tui-actions")?;
+    let mut browser = TrashBrowser::new(TrashFilter::default())?;
+
+    press(&mut browser, "/notes");
+    browser.handle_key(KeyEvent::from(KeyCode::Enter));
+    press(&mut browser, "r");
+    assert!(render(&mut browser)?.contains("Restore 1 item to the original location?"));
+    press(&mut browser, "n");
+    assert_eq!(browser.shown_entries().len(), 1);
+
+    press(&mut browser, "ry");
+    assert_eq!(browser.status(), Some("Restored 1 item"));
+    assert_eq!(read_to_string(test_dir.join("notes.txt"))?, "remember the milk");
+    assert!(browser.shown_entries().is_empty());
+
+    browser.handle_key(KeyEvent::from(KeyCode::Esc));
+    assert_eq!(browser.shown_entries().len(), 1);
+    press(&mut browser, "Ey");
+    assert_eq!(browser.status(), Some("Removed 1 item"));
+    assert!(browser.shown_entries().is_empty());
+    assert!(render(&mut browser)?.contains("No entries"));
+
+    remove_dir_all(test_dir)?;
+    Ok(())
+}